@@ -0,0 +1,715 @@
+use anyhow::{bail, Context as _};
+use camino::{Utf8Path, Utf8PathBuf};
+use cargo_metadata as cm;
+use itertools::Itertools as _;
+use proc_macro2::{Delimiter, LineColumn, Spacing, TokenStream, TokenTree};
+use quote::ToTokens as _;
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    ops::Range,
+};
+use syn::{spanned::Spanned as _, Item};
+
+/// Inlines the library crates in local `path` dependencies that `bin` uses into its source code.
+///
+/// Each library is expanded into `pub mod <lib name>` at the end of the code, and the paths to it
+/// are rewritten into `crate::<lib name>::…`. Top-level modules of the libraries that are not
+/// used, `#[cfg(test)]` items, and doc comments are removed.
+pub(crate) fn bundle(
+    metadata: &cm::Metadata,
+    member: &cm::Package,
+    bin: &cm::Target,
+) -> anyhow::Result<String> {
+    let resolve = metadata
+        .resolve
+        .as_ref()
+        .with_context(|| "`resolve` is missing in the output of `cargo metadata`")?;
+    let nodes = resolve
+        .nodes
+        .iter()
+        .map(|node| (&node.id, node))
+        .collect::<HashMap<_, _>>();
+    let packages = metadata
+        .packages
+        .iter()
+        .map(|package| (&package.id, package))
+        .collect::<HashMap<_, _>>();
+
+    // Extern crate names and the local library crates that `id` depends on.
+    let local_deps = |id: &cm::PackageId| -> Vec<(&str, &cm::PackageId)> {
+        nodes[id]
+            .deps
+            .iter()
+            .filter(|dep| {
+                dep.dep_kinds
+                    .iter()
+                    .any(|k| k.kind == cm::DependencyKind::Normal)
+                    && packages[&dep.pkg].source.is_none()
+                    && lib_target(packages[&dep.pkg]).is_some()
+            })
+            .map(|dep| (&*dep.name, &dep.pkg))
+            .collect()
+    };
+
+    // Dependents come first.
+    let mut order = vec![];
+    topological_sort(&member.id, &local_deps, &mut vec![], &mut order)?;
+    order.reverse();
+    order.retain(|id| *id != &member.id);
+
+    let lib_name = |id: &cm::PackageId| lib_target(packages[id]).unwrap().name.replace('-', "_");
+
+    let code = crate::fs::read_to_string(&bin.src_path)?;
+
+    let mut uses = HashMap::<&cm::PackageId, Uses>::new();
+    let bin_deps = local_deps(&member.id)
+        .into_iter()
+        .map(|(name, id)| (name.to_owned(), (id, lib_name(id))))
+        .collect::<BTreeMap<_, _>>();
+    let code = rewrite_paths(&code, None, &bin_deps, &mut uses)
+        .with_context(|| format!("could not parse `{}`", bin.src_path))?;
+
+    let mut libs = vec![];
+
+    for id in order {
+        let lib_uses = match uses.remove(id) {
+            Some(lib_uses) => lib_uses,
+            None => continue,
+        };
+        let lib = lib_target(packages[id]).unwrap();
+        let name = lib_name(id);
+
+        let lib_code = expand_mods(&lib.src_path, &mod_dir(&lib.src_path, true))?;
+        let lib_code = prune_mods(&lib_code, &lib_uses)?;
+        let lib_deps = local_deps(id)
+            .into_iter()
+            .map(|(name, id)| (name.to_owned(), (id, lib_name(id))))
+            .collect();
+        let mut lib_code = rewrite_paths(&lib_code, Some(&name), &lib_deps, &mut uses)?;
+
+        // `#[macro_export]` macros are placed at the crate root.
+        let macros = exported_macros(&syn::parse_file(&lib_code)?.items);
+        if !macros.is_empty() {
+            lib_code += &format!("pub use crate::{{{}}};\n", macros.iter().format(", "));
+        }
+
+        libs.push((name, lib_code));
+    }
+
+    let mut acc = code;
+    if !libs.is_empty() {
+        acc += "\n// The following code was expanded by `cargo compete submit`.\n";
+        for (name, lib_code) in libs {
+            acc += &format!("\n#[allow(unused)]\npub mod {name} {{\n{lib_code}}}\n");
+        }
+    }
+
+    syn::parse_file(&acc).with_context(|| "the bundled code is broken. probably this is a bug")?;
+    Ok(acc)
+}
+
+fn lib_target(package: &cm::Package) -> Option<&cm::Target> {
+    package
+        .targets
+        .iter()
+        .find(|t| t.kind.iter().any(|k| k == "lib" || k == "rlib"))
+}
+
+fn topological_sort<'a>(
+    id: &'a cm::PackageId,
+    deps: &impl Fn(&cm::PackageId) -> Vec<(&'a str, &'a cm::PackageId)>,
+    visiting: &mut Vec<&'a cm::PackageId>,
+    acc: &mut Vec<&'a cm::PackageId>,
+) -> anyhow::Result<()> {
+    if acc.contains(&id) {
+        return Ok(());
+    }
+    if visiting.contains(&id) {
+        bail!("found a cycle in the dependencies: {}", id);
+    }
+    visiting.push(id);
+    for (_, dep) in deps(id) {
+        topological_sort(dep, deps, visiting, acc)?;
+    }
+    visiting.pop();
+    acc.push(id);
+    Ok(())
+}
+
+/// Names of the top-level modules used through paths like `lib::<name>::…`.
+#[derive(Default, Debug)]
+struct Uses {
+    all: bool,
+    names: BTreeSet<String>,
+}
+
+/// Returns the directory for the submodules of the file.
+fn mod_dir(path: &Utf8Path, is_root: bool) -> Utf8PathBuf {
+    let parent = path.parent().unwrap().to_owned();
+    if is_root || path.file_name() == Some("mod.rs") {
+        parent
+    } else {
+        parent.join(path.file_stem().unwrap())
+    }
+}
+
+/// Reads the file, replacing `mod <name>;` with the contents of the files and removing
+/// `#[cfg(test)]` items and doc comments.
+fn expand_mods(path: &Utf8Path, dir: &Utf8Path) -> anyhow::Result<String> {
+    let code = crate::fs::read_to_string(path)?;
+    let file = syn::parse_file(&code).with_context(|| format!("could not parse `{path}`"))?;
+    let offsets = Offsets::new(&code);
+
+    let mut edits = vec![];
+    expand_mods_in_items(&file.items, &code, &offsets, dir, &mut edits)?;
+
+    for range in doc_comments(code.parse().unwrap(), &offsets) {
+        edits.push((whole_lines(&code, range), "".to_owned()));
+    }
+    Ok(apply(&code, edits))
+}
+
+fn expand_mods_in_items(
+    items: &[Item],
+    code: &str,
+    offsets: &Offsets<'_>,
+    dir: &Utf8Path,
+    edits: &mut Vec<(Range<usize>, String)>,
+) -> anyhow::Result<()> {
+    for item in items {
+        if is_cfg_test(item) {
+            edits.push((whole_lines(code, offsets.span(item.span())), "".to_owned()));
+            continue;
+        }
+        if let Item::Mod(item_mod) = item {
+            if item_mod.attrs.iter().any(|a| a.path.is_ident("path")) {
+                bail!("`#[path]` is not supported: `mod {}`", item_mod.ident);
+            }
+            let name = item_mod.ident.to_string();
+            let name = name.strip_prefix("r#").unwrap_or(&name);
+            match &item_mod.content {
+                Some((_, items)) => {
+                    expand_mods_in_items(items, code, offsets, &dir.join(name), edits)?;
+                }
+                None => {
+                    let candidates = [
+                        dir.join(name).with_extension("rs"),
+                        dir.join(name).join("mod.rs"),
+                    ];
+                    let path = candidates
+                        .iter()
+                        .find(|p| p.exists())
+                        .with_context(|| format!("could not find the file for `mod {name}`"))?;
+                    let content = expand_mods(path, &mod_dir(path, false))?;
+                    let semi = offsets.span(item_mod.semi.unwrap().span);
+                    edits.push((semi, format!(" {{\n{content}}}")));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn is_cfg_test(item: &Item) -> bool {
+    let attrs = match item {
+        Item::Const(item) => &item.attrs,
+        Item::Enum(item) => &item.attrs,
+        Item::ExternCrate(item) => &item.attrs,
+        Item::Fn(item) => &item.attrs,
+        Item::ForeignMod(item) => &item.attrs,
+        Item::Impl(item) => &item.attrs,
+        Item::Macro(item) => &item.attrs,
+        Item::Macro2(item) => &item.attrs,
+        Item::Mod(item) => &item.attrs,
+        Item::Static(item) => &item.attrs,
+        Item::Struct(item) => &item.attrs,
+        Item::Trait(item) => &item.attrs,
+        Item::TraitAlias(item) => &item.attrs,
+        Item::Type(item) => &item.attrs,
+        Item::Union(item) => &item.attrs,
+        Item::Use(item) => &item.attrs,
+        _ => return false,
+    };
+    attrs
+        .iter()
+        .any(|a| a.path.is_ident("cfg") && a.tokens.to_string().replace(' ', "") == "(test)")
+}
+
+/// Returns the ranges of `///`, `//!`, `#[doc = …]`, and `#![doc = …]`.
+fn doc_comments(tokens: TokenStream, offsets: &Offsets<'_>) -> Vec<Range<usize>> {
+    let mut acc = vec![];
+    let tokens = tokens.into_iter().collect::<Vec<_>>();
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            TokenTree::Punct(punct) if punct.as_char() == '#' => {
+                let attr = match (tokens.get(i + 1), tokens.get(i + 2)) {
+                    (Some(TokenTree::Punct(bang)), Some(TokenTree::Group(attr)))
+                        if bang.as_char() == '!' =>
+                    {
+                        attr
+                    }
+                    (Some(TokenTree::Group(attr)), _) => attr,
+                    _ => continue,
+                };
+                let is_doc = attr.delimiter() == Delimiter::Bracket
+                    && matches!(
+                        attr.stream().into_iter().next(),
+                        Some(TokenTree::Ident(ident)) if ident == "doc"
+                    );
+                if is_doc {
+                    acc.push(offsets.of(punct.span().start())..offsets.of(attr.span().end()));
+                }
+            }
+            TokenTree::Group(group) => acc.extend(doc_comments(group.stream(), offsets)),
+            _ => {}
+        }
+    }
+    acc
+}
+
+/// Removes the top-level modules that are not used.
+fn prune_mods(code: &str, uses: &Uses) -> anyhow::Result<String> {
+    let file = syn::parse_file(code)?;
+    let offsets = Offsets::new(code);
+
+    let idents = |tokens: TokenStream| {
+        let mut acc = BTreeSet::new();
+        collect_idents(tokens, &mut acc);
+        acc
+    };
+
+    let mut mods = BTreeMap::new();
+    let mut used = uses.names.clone();
+    for item in &file.items {
+        match item {
+            Item::Mod(item_mod) => {
+                let range = whole_lines(code, offsets.span(item.span()));
+                let idents = idents(item.to_token_stream());
+                mods.insert(item_mod.ident.to_string(), (range, idents));
+            }
+            item => used.extend(idents(item.to_token_stream())),
+        }
+    }
+    if uses.all {
+        used.extend(mods.keys().cloned());
+    }
+
+    let mut stack = used.iter().cloned().collect::<Vec<_>>();
+    while let Some(name) = stack.pop() {
+        if let Some((_, idents)) = mods.get(&name) {
+            for ident in idents {
+                if used.insert(ident.clone()) {
+                    stack.push(ident.clone());
+                }
+            }
+        }
+    }
+
+    let edits = mods
+        .into_iter()
+        .filter(|(name, _)| !used.contains(name))
+        .map(|(_, (range, _))| (range, "".to_owned()))
+        .collect();
+    Ok(apply(code, edits))
+}
+
+fn collect_idents(tokens: TokenStream, acc: &mut BTreeSet<String>) {
+    for token in tokens {
+        match token {
+            TokenTree::Ident(ident) => {
+                acc.insert(ident.to_string());
+            }
+            TokenTree::Group(group) => collect_idents(group.stream(), acc),
+            _ => {}
+        }
+    }
+}
+
+/// Rewrites paths to the bundled libraries.
+///
+/// - `crate::…` → `crate::<self_name>::…` (if `self_name` is given)
+/// - `<dep>::…` and `::<dep>::…` → `crate::<lib name>::…`
+/// - `extern crate <dep>;` is removed
+///
+/// Also records which top-level modules of the dependencies are used.
+fn rewrite_paths<'a>(
+    code: &str,
+    self_name: Option<&str>,
+    deps: &BTreeMap<String, (&'a cm::PackageId, String)>,
+    uses: &mut HashMap<&'a cm::PackageId, Uses>,
+) -> anyhow::Result<String> {
+    let file = syn::parse_file(code)?;
+    let offsets = Offsets::new(code);
+
+    let mut edits = vec![];
+
+    for item in &file.items {
+        if let Item::ExternCrate(item_extern_crate) = item {
+            if let Some((id, lib_name)) = deps.get(&item_extern_crate.ident.to_string()) {
+                uses.entry(id).or_default().all = true;
+                let replacement = match &item_extern_crate.rename {
+                    Some((_, alias)) => format!("use crate::{lib_name} as {alias};"),
+                    None => "".to_owned(),
+                };
+                let range = offsets.span(item.span());
+                let range = if replacement.is_empty() {
+                    whole_lines(code, range)
+                } else {
+                    range
+                };
+                edits.push((range, replacement));
+            }
+        }
+    }
+
+    rewrite_paths_in_tokens(
+        code.parse().unwrap(),
+        &offsets,
+        self_name,
+        deps,
+        uses,
+        &mut edits,
+    );
+    Ok(apply(code, edits))
+}
+
+fn rewrite_paths_in_tokens<'a>(
+    tokens: TokenStream,
+    offsets: &Offsets<'_>,
+    self_name: Option<&str>,
+    deps: &BTreeMap<String, (&'a cm::PackageId, String)>,
+    uses: &mut HashMap<&'a cm::PackageId, Uses>,
+    edits: &mut Vec<(Range<usize>, String)>,
+) {
+    let tokens = tokens.into_iter().collect::<Vec<_>>();
+
+    let is_path_sep = |i: usize| {
+        matches!(
+            (tokens.get(i), tokens.get(i + 1)),
+            (Some(TokenTree::Punct(p1)), Some(TokenTree::Punct(p2)))
+            if p1.as_char() == ':' && p1.spacing() == Spacing::Joint && p2.as_char() == ':'
+        )
+    };
+    let is_punct =
+        |i: usize, c: char| matches!(tokens.get(i), Some(TokenTree::Punct(p)) if p.as_char() == c);
+
+    for (i, token) in tokens.iter().enumerate() {
+        let ident = match token {
+            TokenTree::Ident(ident) => ident,
+            TokenTree::Group(group) => {
+                rewrite_paths_in_tokens(group.stream(), offsets, self_name, deps, uses, edits);
+                continue;
+            }
+            _ => continue,
+        };
+        if !is_path_sep(i + 1) {
+            continue;
+        }
+
+        if let Some(self_name) = self_name {
+            if ident == "crate" {
+                let end = offsets.of(ident.span().end());
+                edits.push((end..end, format!("::{self_name}")));
+                continue;
+            }
+        }
+
+        let (id, lib_name) = match deps.get(&ident.to_string()) {
+            Some(dep) => dep,
+            None => continue,
+        };
+        if i > 0 && (is_punct(i - 1, '.') || is_punct(i - 1, '$')) {
+            continue;
+        }
+        let has_leading_colon = i >= 2 && is_path_sep(i - 2);
+        if has_leading_colon {
+            // Part of another path such as `a::<dep>::…`.
+            let is_leading = match i.checked_sub(3).map(|j| &tokens[j]) {
+                Some(TokenTree::Ident(prev)) => {
+                    ["as", "dyn", "impl", "in", "use"].iter().any(|k| prev == k)
+                }
+                Some(TokenTree::Punct(prev)) => prev.as_char() != '>',
+                Some(TokenTree::Group(_)) => false,
+                _ => true,
+            };
+            if !is_leading {
+                continue;
+            }
+        }
+
+        let start = if has_leading_colon {
+            offsets.of(tokens[i - 2].span().start())
+        } else {
+            offsets.of(ident.span().start())
+        };
+        edits.push((
+            start..offsets.of(ident.span().end()),
+            format!("crate::{lib_name}"),
+        ));
+
+        let uses = uses.entry(id).or_default();
+        match tokens.get(i + 3) {
+            Some(TokenTree::Ident(name)) => {
+                uses.names.insert(name.to_string());
+            }
+            Some(TokenTree::Group(group)) => collect_idents(group.stream(), &mut uses.names),
+            _ => uses.all = true,
+        }
+    }
+}
+
+/// Returns the names of the `#[macro_export]` macros.
+fn exported_macros(items: &[Item]) -> Vec<String> {
+    let mut acc = vec![];
+    for item in items {
+        match item {
+            Item::Macro(item_macro) => {
+                if let Some(ident) = &item_macro.ident {
+                    if item_macro
+                        .attrs
+                        .iter()
+                        .any(|a| a.path.is_ident("macro_export"))
+                    {
+                        acc.push(ident.to_string());
+                    }
+                }
+            }
+            Item::Mod(item_mod) => {
+                if let Some((_, items)) = &item_mod.content {
+                    acc.extend(exported_macros(items));
+                }
+            }
+            _ => {}
+        }
+    }
+    acc
+}
+
+/// Extends `range` to the whole lines if nothing else is on them.
+fn whole_lines(code: &str, range: Range<usize>) -> Range<usize> {
+    let line_start = code[..range.start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = code[range.end..]
+        .find('\n')
+        .map_or(code.len(), |i| range.end + i + 1);
+    if code[line_start..range.start].trim().is_empty()
+        && code[range.end..line_end].trim().is_empty()
+    {
+        line_start..line_end
+    } else {
+        range
+    }
+}
+
+/// Applies the replacements. Ones inside other ones are ignored.
+fn apply(code: &str, mut edits: Vec<(Range<usize>, String)>) -> String {
+    edits.sort_by_key(|(range, _)| (range.start, Reverse(range.end)));
+    let mut acc = "".to_owned();
+    let mut pos = 0;
+    for (range, replacement) in edits {
+        if range.start < pos {
+            continue;
+        }
+        acc += &code[pos..range.start];
+        acc += &replacement;
+        pos = range.end;
+    }
+    acc += &code[pos..];
+    acc
+}
+
+/// Byte offsets of the lines, since `LineColumn::column` counts characters.
+struct Offsets<'a> {
+    code: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> Offsets<'a> {
+    fn new(code: &'a str) -> Self {
+        let line_starts =
+            itertools::chain([0], code.match_indices('\n').map(|(i, _)| i + 1)).collect();
+        Self { code, line_starts }
+    }
+
+    fn of(&self, LineColumn { line, column }: LineColumn) -> usize {
+        let line_start = self.line_starts[line - 1];
+        line_start
+            + self.code[line_start..]
+                .chars()
+                .take(column)
+                .map(char::len_utf8)
+                .sum::<usize>()
+    }
+
+    fn span(&self, span: proc_macro2::Span) -> Range<usize> {
+        self.of(span.start())..self.of(span.end())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bundle::{
+        apply, doc_comments, prune_mods, rewrite_paths, whole_lines, Offsets, Uses,
+    };
+    use cargo_metadata as cm;
+    use maplit::{btreemap, btreeset};
+    use pretty_assertions::assert_eq;
+    use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+    #[test]
+    fn remove_doc_comments() {
+        let code = "//! Crate.\n\n/// A function.\n#[inline]\npub fn f() {\n    //! Body.\n}\n";
+        let offsets = Offsets::new(code);
+        let edits = doc_comments(code.parse().unwrap(), &offsets)
+            .into_iter()
+            .map(|range| (whole_lines(code, range), "".to_owned()))
+            .collect();
+        assert_eq!("\n#[inline]\npub fn f() {\n}\n", apply(code, edits));
+    }
+
+    #[test]
+    fn prune_unused_mods() -> anyhow::Result<()> {
+        let code = r#"pub use self::b::B;
+pub mod a {
+    pub fn a() {}
+}
+pub mod b {
+    pub struct B(crate::c::C);
+}
+pub mod c {
+    pub struct C;
+}
+pub mod d {
+    pub fn d() {}
+}
+"#;
+        let uses = Uses {
+            all: false,
+            names: std::iter::once("a".to_owned()).collect(),
+        };
+        assert_eq!(
+            r#"pub use self::b::B;
+pub mod a {
+    pub fn a() {}
+}
+pub mod b {
+    pub struct B(crate::c::C);
+}
+pub mod c {
+    pub struct C;
+}
+"#,
+            prune_mods(code, &uses)?,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rewrite_dep_paths() -> anyhow::Result<()> {
+        let id = package_id("dep");
+        let deps = deps(&id);
+        let mut uses = HashMap::new();
+
+        let code = r#"use dep::a::f;
+use dep::{b, c::g};
+
+fn main() {
+    ::dep::d::h();
+    other::dep::e();
+    x.dep::<()>();
+}
+"#;
+        assert_eq!(
+            r#"use crate::dep_lib::a::f;
+use crate::dep_lib::{b, c::g};
+
+fn main() {
+    crate::dep_lib::d::h();
+    other::dep::e();
+    x.dep::<()>();
+}
+"#,
+            rewrite_paths(code, None, &deps, &mut uses)?,
+        );
+        assert!(!uses[&id].all);
+        assert_eq!(btreeset!["a", "b", "c", "d", "g"], names(&uses[&id]));
+        Ok(())
+    }
+
+    #[test]
+    fn rewrite_extern_crates() -> anyhow::Result<()> {
+        let id = package_id("dep");
+        let deps = deps(&id);
+        let mut uses = HashMap::new();
+
+        let code = r#"extern crate dep as x;
+extern crate dep;
+
+fn main() {
+    x::a::f();
+}
+"#;
+        assert_eq!(
+            r#"use crate::dep_lib as x;
+
+fn main() {
+    x::a::f();
+}
+"#,
+            rewrite_paths(code, None, &deps, &mut uses)?,
+        );
+        assert!(uses[&id].all);
+        Ok(())
+    }
+
+    #[test]
+    fn rewrite_crate_paths_in_lib() -> anyhow::Result<()> {
+        let mut uses = HashMap::new();
+
+        let code = r#"use crate::a::f;
+
+#[macro_export]
+macro_rules! m {
+    () => {
+        $crate::a::f()
+    };
+}
+
+pub(crate) mod a {
+    pub fn f() {}
+}
+"#;
+        assert_eq!(
+            r#"use crate::my_lib::a::f;
+
+#[macro_export]
+macro_rules! m {
+    () => {
+        $crate::my_lib::a::f()
+    };
+}
+
+pub(crate) mod a {
+    pub fn f() {}
+}
+"#,
+            rewrite_paths(code, Some("my_lib"), &BTreeMap::new(), &mut uses)?,
+        );
+        assert!(uses.is_empty());
+        Ok(())
+    }
+
+    fn package_id(name: &str) -> cm::PackageId {
+        cm::PackageId {
+            repr: format!("{name} 0.0.0 (path+file:///{name})"),
+        }
+    }
+
+    fn deps(id: &cm::PackageId) -> BTreeMap<String, (&cm::PackageId, String)> {
+        btreemap!("dep".to_owned() => (id, "dep_lib".to_owned()))
+    }
+
+    fn names(uses: &Uses) -> BTreeSet<&str> {
+        uses.names.iter().map(|s| &**s).collect()
+    }
+}
@@ -60,6 +60,7 @@ pub(crate) fn run(opt: OptCompeteAdd, ctx: crate::Context<'_>) -> anyhow::Result
         cwd,
         cookies_path,
         shell,
+        cargo_compete_exe: _,
     } = ctx;
 
     shell.set_color_choice(color);
@@ -0,0 +1,40 @@
+use std::ffi::OsString;
+use structopt::StructOpt;
+
+/// Wrapper that `test` uses to run a program with `test.stack-size`
+#[derive(StructOpt, Debug)]
+pub struct OptCompeteExecWithStackSize {
+    /// Stack size in bytes
+    #[structopt(long, value_name("BYTES"))]
+    pub stack_size: u64,
+
+    /// Program and its arguments
+    #[structopt(required(true), min_values(1), last(true))]
+    pub cmd: Vec<OsString>,
+}
+
+pub(crate) fn run(opt: OptCompeteExecWithStackSize, _: crate::Context<'_>) -> anyhow::Result<()> {
+    let OptCompeteExecWithStackSize { stack_size, cmd } = opt;
+    exec(stack_size, &cmd)
+}
+
+#[cfg(unix)]
+fn exec(stack_size: u64, cmd: &[OsString]) -> anyhow::Result<()> {
+    use anyhow::Context as _;
+    use rustix::process::{getrlimit, setrlimit, Resource, Rlimit};
+    use std::os::unix::process::CommandExt as _;
+
+    // The soft limit cannot be raised above the hard limit without privileges.
+    let Rlimit { maximum, .. } = getrlimit(Resource::Stack);
+    let current = Some(maximum.map_or(stack_size, |maximum| stack_size.min(maximum)));
+    setrlimit(Resource::Stack, Rlimit { current, maximum })
+        .with_context(|| "could not set the stack size")?;
+
+    let err = std::process::Command::new(&cmd[0]).args(&cmd[1..]).exec();
+    Err(err).with_context(|| format!("could not execute `{}`", cmd[0].to_string_lossy()))
+}
+
+#[cfg(not(unix))]
+fn exec(_: u64, _: &[OsString]) -> anyhow::Result<()> {
+    anyhow::bail!("`test.stack-size` is only supported on Unix")
+}
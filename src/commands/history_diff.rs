@@ -0,0 +1,52 @@
+use crate::shell::ColorChoice;
+use std::path::PathBuf;
+use structopt::StructOpt;
+use strum::VariantNames as _;
+
+#[derive(StructOpt, Debug)]
+pub struct OptCompeteHistoryDiff {
+    /// Path to Cargo.toml
+    #[structopt(long, value_name("PATH"))]
+    pub manifest_path: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        value_name("WHEN"),
+        possible_values(ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: ColorChoice,
+
+    /// ID of the old submission. See `cargo compete history list`
+    pub id1: usize,
+
+    /// ID of the new submission
+    pub id2: usize,
+}
+
+pub(crate) fn run(opt: OptCompeteHistoryDiff, ctx: crate::Context<'_>) -> anyhow::Result<()> {
+    let OptCompeteHistoryDiff {
+        manifest_path,
+        color,
+        id1,
+        id2,
+    } = opt;
+
+    let crate::Context { cwd, shell, .. } = ctx;
+
+    shell.set_color_choice(color);
+
+    let manifest_path = manifest_path
+        .map(|p| Ok(cwd.join(p.strip_prefix(".").unwrap_or(&p))))
+        .unwrap_or_else(|| crate::project::locate_project(&cwd))?;
+    let metadata = crate::project::cargo_metadata(manifest_path, &cwd)?;
+    let cargo_compete_config_path = crate::config::locate(&metadata.workspace_root, None::<&str>)?;
+
+    crate::submission_history::diff(
+        &crate::submission_history::history_path(&cargo_compete_config_path),
+        id1,
+        id2,
+        shell,
+    )
+}
@@ -0,0 +1,54 @@
+use crate::shell::ColorChoice;
+use std::path::PathBuf;
+use structopt::StructOpt;
+use strum::VariantNames as _;
+
+#[derive(StructOpt, Debug)]
+pub struct OptCompeteHistoryList {
+    /// List only the submissions of the `bin`/`example`
+    #[structopt(long, value_name("NAME_OR_ALIAS"))]
+    pub bin: Option<String>,
+
+    /// List only the submissions for the contest
+    #[structopt(long, value_name("CONTEST_ID"))]
+    pub contest: Option<String>,
+
+    /// Path to Cargo.toml
+    #[structopt(long, value_name("PATH"))]
+    pub manifest_path: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        value_name("WHEN"),
+        possible_values(ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: ColorChoice,
+}
+
+pub(crate) fn run(opt: OptCompeteHistoryList, ctx: crate::Context<'_>) -> anyhow::Result<()> {
+    let OptCompeteHistoryList {
+        bin,
+        contest,
+        manifest_path,
+        color,
+    } = opt;
+
+    let crate::Context { cwd, shell, .. } = ctx;
+
+    shell.set_color_choice(color);
+
+    let manifest_path = manifest_path
+        .map(|p| Ok(cwd.join(p.strip_prefix(".").unwrap_or(&p))))
+        .unwrap_or_else(|| crate::project::locate_project(&cwd))?;
+    let metadata = crate::project::cargo_metadata(manifest_path, &cwd)?;
+    let cargo_compete_config_path = crate::config::locate(&metadata.workspace_root, None::<&str>)?;
+
+    crate::submission_history::list(
+        &crate::submission_history::history_path(&cargo_compete_config_path),
+        bin.as_deref(),
+        contest.as_deref(),
+        shell,
+    )
+}
@@ -48,6 +48,7 @@ pub(crate) fn run(opt: OptCompeteInit, ctx: crate::Context<'_>) -> anyhow::Resul
         cwd,
         cookies_path: _,
         shell,
+        cargo_compete_exe: _,
     } = ctx;
 
     shell.set_color_choice(color);
@@ -34,6 +34,7 @@ pub(crate) fn run(opt: OptCompeteLogin, ctx: crate::Context<'_>) -> anyhow::Resu
         cwd: _,
         cookies_path,
         shell,
+        cargo_compete_exe: _,
     } = ctx;
 
     shell.set_color_choice(color);
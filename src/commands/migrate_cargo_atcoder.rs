@@ -58,6 +58,7 @@ pub(crate) fn run(
         cwd,
         cookies_path: _,
         shell,
+        cargo_compete_exe: _,
     } = ctx;
 
     shell.set_color_choice(color);
@@ -0,0 +1,164 @@
+use crate::{
+    config::CargoCompeteConfigTestProfile,
+    project::{MetadataExt as _, PackageExt as _},
+    shell::ColorChoice,
+    testing::minimize::{Granularity, Source},
+};
+use human_size::Size;
+use std::path::PathBuf;
+use structopt::StructOpt;
+use strum::VariantNames as _;
+
+#[derive(StructOpt, Debug)]
+#[structopt(usage(
+    r"cargo compete minimize [OPTIONS] --input <PATH> <bin-name-or-alias>
+    cargo compete minimize [OPTIONS] --testcase <NAME> <bin-name-or-alias>",
+))]
+pub struct OptCompeteMinimize {
+    /// Path to the failing input
+    #[structopt(
+        long,
+        value_name("PATH"),
+        required_unless("testcase"),
+        conflicts_with("testcase")
+    )]
+    pub input: Option<PathBuf>,
+
+    /// Name of the failing test case
+    #[structopt(long, value_name("NAME"))]
+    pub testcase: Option<String>,
+
+    /// Name or alias for a `bin`/`example` that prints the correct output
+    #[structopt(long, value_name("NAME"))]
+    pub reference: Option<String>,
+
+    /// Unit of the input to remove
+    #[structopt(
+        long,
+        value_name("UNIT"),
+        possible_values(Granularity::VARIANTS),
+        default_value("lines")
+    )]
+    pub granularity: Granularity,
+
+    /// Number of leading lines to keep as they are
+    #[structopt(long, value_name("N"), default_value("0"))]
+    pub fixed_lines: usize,
+
+    /// Display limit
+    #[structopt(long, value_name("SIZE"), default_value("4KiB"))]
+    pub display_limit: Size,
+
+    /// Show where the outputs differ for wrong answers. Also enabled by `test.diff` in compete.toml
+    #[structopt(long)]
+    pub diff: bool,
+
+    /// Existing package
+    #[structopt(short, long, value_name("SPEC"))]
+    pub package: Option<String>,
+
+    /// Build in debug mode. Overrides `test.profile` in compete.toml
+    #[structopt(long, conflicts_with("release"))]
+    pub debug: bool,
+
+    /// Build in release mode. Overrides `test.profile` in compete.toml
+    #[structopt(long)]
+    pub release: bool,
+
+    /// Path to Cargo.toml
+    #[structopt(long, value_name("PATH"))]
+    pub manifest_path: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        value_name("WHEN"),
+        possible_values(ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: ColorChoice,
+
+    /// Name or alias for a `bin`/`example`
+    pub name_or_alias: String,
+}
+
+pub(crate) fn run(opt: OptCompeteMinimize, ctx: crate::Context<'_>) -> anyhow::Result<()> {
+    let OptCompeteMinimize {
+        input,
+        testcase,
+        reference,
+        granularity,
+        fixed_lines,
+        display_limit,
+        diff,
+        package,
+        debug,
+        release,
+        manifest_path,
+        color,
+        name_or_alias,
+    } = opt;
+
+    let crate::Context {
+        cwd,
+        cookies_path,
+        shell,
+        cargo_compete_exe,
+    } = ctx;
+
+    shell.set_color_choice(color);
+
+    let manifest_path = manifest_path
+        .map(|p| Ok(cwd.join(p.strip_prefix(".").unwrap_or(&p))))
+        .unwrap_or_else(|| crate::project::locate_project(&cwd))?;
+    let metadata = crate::project::cargo_metadata(manifest_path, &cwd)?;
+    let member = metadata.query_for_member(package.as_deref())?;
+    let package_metadata = member.read_package_metadata(shell)?;
+    let (cargo_compete_config, _) = crate::config::load_for_package(member, shell)?;
+
+    let (bin_name, pkg_md_bin_example) =
+        package_metadata.bin_like_by_name_or_alias(&name_or_alias)?;
+    let bin = member.bin_like_target_by_name(bin_name)?;
+
+    let reference = reference
+        .map(|r| member.bin_like_target_by_name_or_alias(&package_metadata, &r))
+        .transpose()?;
+
+    let input = input.map(|p| cwd.join(p.strip_prefix(".").unwrap_or(&p)));
+
+    let source = if let Some(input) = &input {
+        Source::File(input)
+    } else if let Some(testcase) = &testcase {
+        Source::TestCase(testcase)
+    } else {
+        unreachable!()
+    };
+
+    crate::testing::minimize::minimize(crate::testing::minimize::Args {
+        metadata: &metadata,
+        member,
+        bin,
+        bin_alias: &pkg_md_bin_example.alias,
+        reference,
+        cargo_compete_config_test_suite: &cargo_compete_config.test_suite,
+        problem_url: &pkg_md_bin_example.problem,
+        toolchain: cargo_compete_config.test.toolchain.as_deref(),
+        checker: cargo_compete_config.test.checker.as_ref(),
+        run: &cargo_compete_config.test.run,
+        release: if debug {
+            false
+        } else if release {
+            true
+        } else {
+            cargo_compete_config.test.profile == CargoCompeteConfigTestProfile::Release
+        },
+        source,
+        granularity,
+        fixed_lines,
+        display_limit,
+        diff: diff || cargo_compete_config.test.diff,
+        cookies_path: &cookies_path,
+        cargo_compete_exe: cargo_compete_exe.as_deref(),
+        shell,
+    })
+}
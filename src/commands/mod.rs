@@ -1,12 +1,23 @@
 pub(crate) mod add;
+pub(crate) mod exec_with_stack_size;
+pub(crate) mod history_diff;
+pub(crate) mod history_list;
+pub(crate) mod history_show;
 pub(crate) mod init;
 pub(crate) mod login;
 pub(crate) mod migrate_cargo_atcoder;
+pub(crate) mod minimize;
 pub(crate) mod new;
 pub(crate) mod open;
 pub(crate) mod participate;
 pub(crate) mod retrieve_submission_summaries;
 pub(crate) mod retrieve_testcases;
+pub(crate) mod run;
+pub(crate) mod stress;
 pub(crate) mod submit;
 pub(crate) mod test;
+pub(crate) mod testcase_add;
+pub(crate) mod testcase_list;
+pub(crate) mod testcase_remove;
+pub(crate) mod testcase_rename;
 pub(crate) mod watch_submissions;
@@ -59,6 +59,7 @@ pub fn run(opt: OptCompeteNew, ctx: crate::Context<'_>) -> anyhow::Result<()> {
         cwd,
         cookies_path,
         shell,
+        cargo_compete_exe: _,
     } = ctx;
 
     shell.set_color_choice(color);
@@ -53,6 +53,7 @@ pub(crate) fn run(opt: OptCompeteOpen, ctx: crate::Context<'_>) -> anyhow::Resul
         cwd,
         cookies_path,
         shell,
+        cargo_compete_exe: _,
     } = ctx;
 
     shell.set_color_choice(color);
@@ -74,19 +75,20 @@ pub(crate) fn run(opt: OptCompeteOpen, ctx: crate::Context<'_>) -> anyhow::Resul
     let mut file_paths = vec![];
     let mut missing = [hashset!(), hashset!()];
 
-    for (i, (name, PackageMetadataCargoCompeteBinExample { alias, problem })) in itertools::chain(
-        package_metadata.bin.iter().filter(
-            |&(name, PackageMetadataCargoCompeteBinExample { alias, .. })| {
-                bin.map_or(true, |s| s.contains(name) || s.contains(alias))
-            },
-        ),
-        package_metadata.example.iter().filter(
-            |&(name, PackageMetadataCargoCompeteBinExample { alias, .. })| {
-                example.map_or(true, |s| s.contains(name) || s.contains(alias))
-            },
-        ),
-    )
-    .enumerate()
+    for (i, (name, PackageMetadataCargoCompeteBinExample { alias, problem, .. })) in
+        itertools::chain(
+            package_metadata.bin.iter().filter(
+                |&(name, PackageMetadataCargoCompeteBinExample { alias, .. })| {
+                    bin.map_or(true, |s| s.contains(name) || s.contains(alias))
+                },
+            ),
+            package_metadata.example.iter().filter(
+                |&(name, PackageMetadataCargoCompeteBinExample { alias, .. })| {
+                    example.map_or(true, |s| s.contains(name) || s.contains(alias))
+                },
+            ),
+        )
+        .enumerate()
     {
         urls.push(problem.clone());
 
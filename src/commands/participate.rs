@@ -41,6 +41,7 @@ pub(crate) fn run(opt: OptCompeteParticipate, ctx: crate::Context<'_>) -> anyhow
         cwd: _,
         cookies_path,
         shell,
+        cargo_compete_exe: _,
     } = ctx;
 
     shell.set_color_choice(color);
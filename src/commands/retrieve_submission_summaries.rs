@@ -52,6 +52,7 @@ pub(crate) fn run(
         cwd,
         cookies_path,
         shell,
+        cargo_compete_exe: _,
     } = ctx;
 
     shell.set_color_choice(color);
@@ -0,0 +1,112 @@
+use crate::{
+    config::CargoCompeteConfigTestProfile,
+    project::{MetadataExt as _, PackageExt as _},
+    shell::ColorChoice,
+    testing::run::Input,
+};
+use std::path::PathBuf;
+use structopt::StructOpt;
+use strum::VariantNames as _;
+
+#[derive(StructOpt, Debug)]
+pub struct OptCompeteRun {
+    /// Path to the input. Defaults to the standard input
+    #[structopt(long, value_name("PATH"), conflicts_with("input-text"))]
+    pub input: Option<PathBuf>,
+
+    /// Input text. A newline is appended if it does not end with one
+    #[structopt(long, value_name("TEXT"))]
+    pub input_text: Option<String>,
+
+    /// Existing package to run
+    #[structopt(short, long, value_name("SPEC"))]
+    pub package: Option<String>,
+
+    /// Build in debug mode. Overrides `test.profile` in compete.toml
+    #[structopt(long, conflicts_with("release"))]
+    pub debug: bool,
+
+    /// Build in release mode. Overrides `test.profile` in compete.toml
+    #[structopt(long)]
+    pub release: bool,
+
+    /// Path to Cargo.toml
+    #[structopt(long, value_name("PATH"))]
+    pub manifest_path: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        value_name("WHEN"),
+        possible_values(ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: ColorChoice,
+
+    /// Name or alias for a `bin`/`example`
+    pub name_or_alias: String,
+}
+
+pub(crate) fn run(opt: OptCompeteRun, ctx: crate::Context<'_>) -> anyhow::Result<()> {
+    let OptCompeteRun {
+        input,
+        input_text,
+        package,
+        debug,
+        release,
+        manifest_path,
+        color,
+        name_or_alias,
+    } = opt;
+
+    let crate::Context {
+        cwd,
+        shell,
+        cargo_compete_exe,
+        ..
+    } = ctx;
+
+    shell.set_color_choice(color);
+
+    let manifest_path = manifest_path
+        .map(|p| Ok(cwd.join(p.strip_prefix(".").unwrap_or(&p))))
+        .unwrap_or_else(|| crate::project::locate_project(&cwd))?;
+    let metadata = crate::project::cargo_metadata(manifest_path, &cwd)?;
+    let member = metadata.query_for_member(package.as_deref())?;
+    let package_metadata = member.read_package_metadata(shell)?;
+    let (cargo_compete_config, _) = crate::config::load_for_package(member, shell)?;
+
+    let (bin_name, pkg_md_bin_example) =
+        package_metadata.bin_like_by_name_or_alias(&name_or_alias)?;
+    let bin = member.bin_like_target_by_name(bin_name)?;
+
+    let input = input.map(|p| cwd.join(p.strip_prefix(".").unwrap_or(&p)));
+
+    let input = if let Some(input) = &input {
+        Input::File(input)
+    } else if let Some(input_text) = &input_text {
+        Input::Text(input_text)
+    } else {
+        Input::Inherit
+    };
+
+    crate::testing::run::run(crate::testing::run::Args {
+        metadata: &metadata,
+        member,
+        bin,
+        bin_alias: &pkg_md_bin_example.alias,
+        problem_url: &pkg_md_bin_example.problem,
+        toolchain: cargo_compete_config.test.toolchain.as_deref(),
+        run: &cargo_compete_config.test.run,
+        release: if debug {
+            false
+        } else if release {
+            true
+        } else {
+            cargo_compete_config.test.profile == CargoCompeteConfigTestProfile::Release
+        },
+        input,
+        cargo_compete_exe: cargo_compete_exe.as_deref(),
+        shell,
+    })
+}
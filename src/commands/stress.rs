@@ -0,0 +1,151 @@
+use crate::{
+    config::CargoCompeteConfigTestProfile,
+    project::{MetadataExt as _, PackageExt as _},
+    shell::ColorChoice,
+};
+use human_size::Size;
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use structopt::StructOpt;
+use strum::VariantNames as _;
+
+#[derive(StructOpt, Debug)]
+#[structopt(usage(
+    "cargo compete stress [OPTIONS] --generator <NAME> --reference <NAME> <bin-name-or-alias>"
+))]
+pub struct OptCompeteStress {
+    /// Name or alias for a `bin`/`example` that prints an input. The seed is given as the first argument
+    #[structopt(long, value_name("NAME"))]
+    pub generator: String,
+
+    /// Name or alias for a `bin`/`example` that prints the correct output
+    #[structopt(long, value_name("NAME"))]
+    pub reference: String,
+
+    /// Number of iterations [default: 100 unless `--duration` is specified]
+    #[structopt(short = "n", long, value_name("N"))]
+    pub iterations: Option<u64>,
+
+    /// Time budget (e.g. "30s", "5m")
+    #[structopt(long, value_name("DURATION"), parse(try_from_str = humantime::parse_duration))]
+    pub duration: Option<Duration>,
+
+    /// Seed for the first iteration. Incremented for each iteration [default: random]
+    #[structopt(long, value_name("U64"))]
+    pub seed: Option<u64>,
+
+    /// Display limit
+    #[structopt(long, value_name("SIZE"), default_value("4KiB"))]
+    pub display_limit: Size,
+
+    /// Show where the outputs differ for wrong answers. Also enabled by `test.diff` in compete.toml
+    #[structopt(long)]
+    pub diff: bool,
+
+    /// Existing package to stress-test
+    #[structopt(short, long, value_name("SPEC"))]
+    pub package: Option<String>,
+
+    /// Build in debug mode. Overrides `test.profile` in compete.toml
+    #[structopt(long, conflicts_with("release"))]
+    pub debug: bool,
+
+    /// Build in release mode. Overrides `test.profile` in compete.toml
+    #[structopt(long)]
+    pub release: bool,
+
+    /// Path to Cargo.toml
+    #[structopt(long, value_name("PATH"))]
+    pub manifest_path: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        value_name("WHEN"),
+        possible_values(ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: ColorChoice,
+
+    /// Name or alias for a `bin`/`example`
+    pub name_or_alias: String,
+}
+
+pub(crate) fn run(opt: OptCompeteStress, ctx: crate::Context<'_>) -> anyhow::Result<()> {
+    let OptCompeteStress {
+        generator,
+        reference,
+        iterations,
+        duration,
+        seed,
+        display_limit,
+        diff,
+        package,
+        debug,
+        release,
+        manifest_path,
+        color,
+        name_or_alias,
+    } = opt;
+
+    let crate::Context {
+        cwd,
+        shell,
+        cargo_compete_exe,
+        ..
+    } = ctx;
+
+    shell.set_color_choice(color);
+
+    let manifest_path = manifest_path
+        .map(|p| Ok(cwd.join(p.strip_prefix(".").unwrap_or(&p))))
+        .unwrap_or_else(|| crate::project::locate_project(&cwd))?;
+    let metadata = crate::project::cargo_metadata(manifest_path, &cwd)?;
+    let member = metadata.query_for_member(package.as_deref())?;
+    let package_metadata = member.read_package_metadata(shell)?;
+    let (cargo_compete_config, _) = crate::config::load_for_package(member, shell)?;
+
+    let (bin_name, pkg_md_bin_example) =
+        package_metadata.bin_like_by_name_or_alias(&name_or_alias)?;
+    let bin = member.bin_like_target_by_name(bin_name)?;
+
+    let generator = member.bin_like_target_by_name_or_alias(&package_metadata, &generator)?;
+    let reference = member.bin_like_target_by_name_or_alias(&package_metadata, &reference)?;
+
+    let seed = seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default()
+    });
+
+    crate::testing::stress::stress(crate::testing::stress::Args {
+        metadata: &metadata,
+        member,
+        bin,
+        bin_alias: &pkg_md_bin_example.alias,
+        generator,
+        reference,
+        cargo_compete_config_test_suite: &cargo_compete_config.test_suite,
+        problem_url: &pkg_md_bin_example.problem,
+        toolchain: cargo_compete_config.test.toolchain.as_deref(),
+        checker: cargo_compete_config.test.checker.as_ref(),
+        run: &cargo_compete_config.test.run,
+        release: if debug {
+            false
+        } else if release {
+            true
+        } else {
+            cargo_compete_config.test.profile == CargoCompeteConfigTestProfile::Release
+        },
+        iterations,
+        duration,
+        seed,
+        display_limit,
+        diff: diff || cargo_compete_config.test.diff,
+        cargo_compete_exe: cargo_compete_exe.as_deref(),
+        shell,
+    })
+}
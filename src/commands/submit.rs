@@ -1,26 +1,25 @@
 use crate::{
     config::{
-        CargoCompeteConfigSubmit, CargoCompeteConfigSubmitCommand, CargoCompeteConfigSubmitFile,
+        CargoCompeteConfigSubmit, CargoCompeteConfigSubmitBundle, CargoCompeteConfigSubmitCommand,
+        CargoCompeteConfigSubmitFile, CargoCompeteConfigTestProfile,
     },
     oj_api,
     project::{MetadataExt as _, PackageExt as _},
     shell::{ColorChoice, Shell},
+    submission_history::Record,
     web::{credentials, ATCODER_RUST_LANG_ID, CODEFORCES_RUST_LANG_ID, YUKICODER_RUST_LANG_ID},
 };
 use anyhow::{bail, Context as _};
-use human_size::Size;
-use liquid::object;
-use prettytable::{
-    format::{FormatBuilder, LinePosition, LineSeparator},
-    row, Row, Table,
-};
+use az::SaturatingAs as _;
+use human_size::{Byte, Size};
+use prettytable::{row, Row};
 use snowchains_core::web::{
     Atcoder, AtcoderSubmitCredentials, AtcoderWatchSubmissionsCredentials,
     AtcoderWatchSubmissionsTarget, Codeforces, CodeforcesSubmitCredentials, CookieStorage,
     PlatformKind, ProblemInContest, Submit, WatchSubmissions, Yukicoder,
     YukicoderSubmitCredentials, YukicoderSubmitTarget,
 };
-use std::{borrow::BorrowMut as _, cell::RefCell, env, io, iter, path::PathBuf};
+use std::{borrow::BorrowMut as _, cell::RefCell, env, io, iter, num::NonZeroUsize, path::PathBuf};
 use structopt::StructOpt;
 use strum::VariantNames as _;
 use url::Url;
@@ -35,10 +34,22 @@ pub struct OptCompeteSubmit {
     #[structopt(long)]
     pub no_test: bool,
 
+    /// Test the code to submit instead of the `bin`/`example`. See `cargo compete test --help`
+    #[structopt(long, conflicts_with("no-test"))]
+    pub test_submission_payload: bool,
+
     /// Do not watch the submission
     #[structopt(long)]
     pub no_watch: bool,
 
+    /// Show the code to submit and the settings for the submission without submitting it
+    #[structopt(long)]
+    pub dry_run: bool,
+
+    /// Path to write the code to with `--dry-run`. Defaults to the standard output
+    #[structopt(long, value_name("PATH"), requires("dry-run"))]
+    pub output: Option<PathBuf>,
+
     /// Path to the source code
     #[structopt(
         long,
@@ -52,6 +63,14 @@ pub struct OptCompeteSubmit {
     #[structopt(long, value_name("NAME"))]
     pub testcases: Option<Vec<String>>,
 
+    /// Number of test cases to run in parallel. Overrides `test.jobs` in compete.toml
+    #[structopt(short, long, value_name("N"))]
+    pub jobs: Option<NonZeroUsize>,
+
+    /// Memory limit for the test. Overrides `memorylimit` in the test suite
+    #[structopt(long, value_name("SIZE"))]
+    pub memory_limit: Option<Size>,
+
     /// Display limit for the test
     #[structopt(long, value_name("SIZE"), default_value("4KiB"))]
     pub display_limit: Size,
@@ -60,11 +79,11 @@ pub struct OptCompeteSubmit {
     #[structopt(short, long, value_name("SPEC"))]
     pub package: Option<String>,
 
-    /// When testing, build in debug mode. Overrides `test.profile` in compete.toml
+    /// When testing or compiling the bundled code, build in debug mode. Overrides `test.profile` in compete.toml
     #[structopt(long, conflicts_with("release"))]
     pub debug: bool,
 
-    /// When testing, build in release mode. Overrides `test.profile` in compete.toml
+    /// When testing or compiling the bundled code, build in release mode. Overrides `test.profile` in compete.toml
     #[structopt(long)]
     pub release: bool,
 
@@ -89,9 +108,14 @@ pub struct OptCompeteSubmit {
 pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Result<()> {
     let OptCompeteSubmit {
         no_test,
+        test_submission_payload,
         no_watch,
+        dry_run,
+        output,
         src,
         testcases,
+        jobs,
+        memory_limit,
         display_limit,
         package,
         debug,
@@ -105,6 +129,7 @@ pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Res
         cwd,
         cookies_path,
         shell,
+        cargo_compete_exe: _,
     } = ctx;
 
     shell.set_color_choice(color);
@@ -149,15 +174,26 @@ pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Res
         shell.warn("```")?;
     }
 
-    let language_id = match &cargo_compete_config.submit {
-        CargoCompeteConfigSubmit::File(CargoCompeteConfigSubmitFile { language_id, .. })
+    let (language_id, size_limit) = match &cargo_compete_config.submit {
+        CargoCompeteConfigSubmit::File(CargoCompeteConfigSubmitFile {
+            language_id,
+            size_limit,
+            ..
+        })
         | CargoCompeteConfigSubmit::Command(CargoCompeteConfigSubmitCommand {
-            language_id, ..
+            language_id,
+            size_limit,
+            ..
+        })
+        | CargoCompeteConfigSubmit::Bundle(CargoCompeteConfigSubmitBundle {
+            language_id,
+            size_limit,
         })
         | CargoCompeteConfigSubmit::DeprecatedTranspileCommand(CargoCompeteConfigSubmitCommand {
             language_id,
+            size_limit,
             ..
-        }) => language_id.as_deref(),
+        }) => (language_id.as_deref(), *size_limit),
     };
 
     if language_id.is_none() {
@@ -179,6 +215,21 @@ pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Res
             } else {
                 vec![]
             })
+            .args(&if let Some(jobs) = jobs {
+                vec!["--jobs".to_owned(), jobs.to_string()]
+            } else {
+                vec![]
+            })
+            .args(&if let Some(memory_limit) = memory_limit {
+                vec!["--memory-limit".to_owned(), memory_limit.to_string()]
+            } else {
+                vec![]
+            })
+            .args(if test_submission_payload {
+                &["--submission-payload"]
+            } else {
+                &[]
+            })
             .args(&["--display-limit", &display_limit.to_string()])
             .args(if debug {
                 &["--debug"]
@@ -193,60 +244,100 @@ pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Res
             .exec_with_shell_status(shell)?;
     }
 
-    let code = match &cargo_compete_config.submit {
-        CargoCompeteConfigSubmit::File(CargoCompeteConfigSubmitFile { path, .. }) => {
-            let contest = match PlatformKind::from_url(&package_metadata_bin.problem) {
-                Ok(PlatformKind::Atcoder) => Some(crate::web::url::atcoder_contest(
-                    &package_metadata_bin.problem,
-                )?),
-                Ok(PlatformKind::Codeforces) => {
-                    crate::web::url::codeforces_contest(&package_metadata_bin.problem).ok()
-                }
-                Ok(PlatformKind::Yukicoder) => {
-                    crate::web::url::yukicoder_contest(&package_metadata_bin.problem).ok()
-                }
-                _ => todo!(),
-            };
-            let path = path.render(&object!({
-                "manifest_dir": member.manifest_dir(),
-                "contest": contest,
-                "bin_name": &bin.name,
-                "bin_alias": &package_metadata_bin.alias,
-                "src_path": &bin.src_path,
-            }))?;
-            crate::fs::read_to_string(path)?
-        }
-        CargoCompeteConfigSubmit::Command(CargoCompeteConfigSubmitCommand { args, .. })
-        | CargoCompeteConfigSubmit::DeprecatedTranspileCommand(CargoCompeteConfigSubmitCommand {
-            args,
-            ..
-        }) => {
-            if args.is_empty() {
-                bail!("`submit.transpile.args` is empty");
-            }
+    let code = crate::testing::payload::payload(
+        &cargo_compete_config.submit,
+        &metadata,
+        member,
+        bin,
+        &package_metadata_bin.alias,
+        &package_metadata_bin.problem,
+        shell,
+    )?;
+
+    // Make sure that the code compiles without the libraries, unless it has been tested.
+    if matches!(
+        cargo_compete_config.submit,
+        CargoCompeteConfigSubmit::Bundle(_)
+    ) && !test_submission_payload
+    {
+        crate::testing::payload::build(
+            &metadata,
+            member,
+            bin,
+            &code,
+            &cargo_compete_config,
+            &cargo_compete_config_path,
+            if debug {
+                false
+            } else if release {
+                true
+            } else {
+                cargo_compete_config.test.profile == CargoCompeteConfigTestProfile::Release
+            },
+            shell,
+        )
+        .with_context(|| "the bundled code could not be compiled")?;
+    }
 
-            let vars = object!({ "bin_name": &bin.name });
+    let source_code_len = code.len();
+
+    let size_limit = match (size_limit, &backend) {
+        (Some(size_limit), _) => Some(size_limit.into::<Byte>().value().saturating_as()),
+        (None, Backend::Builtin(platform)) => default_size_limit(*platform),
+        (None, Backend::Oj) => None,
+    };
 
-            let args = args
-                .iter()
-                .map(|t| t.render(&vars))
-                .collect::<Result<Vec<_>, _>>()?;
+    // With `--dry-run`, the code is still shown so that it can be inspected.
+    let size_limit_violation = size_limit
+        .filter(|&size_limit| source_code_len > size_limit)
+        .map(|size_limit| {
+            format!(
+                "the code is {} bytes, which exceeds the size limit ({} bytes). minify it (e.g. \
+                 `cargo equip --remove docs --minify libs`) or override the limit with \
+                 `submit.size_limit` in `{}`",
+                source_code_len, size_limit, cargo_compete_config_path,
+            )
+        });
 
-            crate::process::with_which(&args[0], &metadata.workspace_root)?
-                .args(&args[1..])
-                .read_with_shell_status(shell)
-                .with_context(|| "could not transpile the code")?
+    if dry_run {
+        if let Some(output) = output {
+            let output = cwd.join(output.strip_prefix(".").unwrap_or(&output));
+            if let Some(parent) = output.parent().filter(|p| !p.as_os_str().is_empty()) {
+                crate::fs::create_dir_all(parent)?;
+            }
+            crate::fs::write(output, &code)?;
+        } else {
+            shell.out().write_all(code.as_ref())?;
+            shell.out().flush()?;
         }
-    };
 
-    let source_code_len = code.len();
+        let (method, language_id) = match backend {
+            Backend::Builtin(platform) => (
+                "cargo-compete",
+                language_id.unwrap_or_else(|| default_language_id(platform)),
+            ),
+            Backend::Oj => ("oj-api", language_id.unwrap_or("(guessed by oj-api)")),
+        };
+
+        shell.print_table(&[
+            row!["Method", method],
+            row!["Language ID", language_id],
+            row!["Size", source_code_len],
+            row!["URL (problem)", package_metadata_bin.problem],
+        ])?;
+        if let Some(size_limit_violation) = size_limit_violation {
+            shell.warn(size_limit_violation)?;
+        }
+        shell.status("Skipped", "the submission (dry run)")?;
+        return Ok(());
+    }
+
+    if let Some(size_limit_violation) = size_limit_violation {
+        bail!("{}", size_limit_violation);
+    }
 
     if let Backend::Builtin(platform) = backend {
-        let language_id = language_id.unwrap_or(match platform {
-            PlatformKind::Atcoder => ATCODER_RUST_LANG_ID,
-            PlatformKind::Codeforces => CODEFORCES_RUST_LANG_ID,
-            PlatformKind::Yukicoder => YUKICODER_RUST_LANG_ID,
-        });
+        let language_id = language_id.unwrap_or_else(|| default_language_id(platform));
 
         let cookie_storage = CookieStorage::with_jsonl(&cookies_path)?;
         let timeout = crate::web::TIMEOUT;
@@ -269,7 +360,7 @@ pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Res
                     },
                     credentials,
                     language_id: language_id.to_owned(),
-                    code,
+                    code: code.clone(),
                     watch_submission: false,
                     cookie_storage,
                     timeout,
@@ -297,7 +388,7 @@ pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Res
                     },
                     credentials,
                     language_id: language_id.to_owned(),
-                    code,
+                    code: code.clone(),
                     watch_submission: false,
                     cookie_storage,
                     timeout,
@@ -313,7 +404,7 @@ pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Res
                     target: YukicoderSubmitTarget::Url(package_metadata_bin.problem.clone()),
                     credentials,
                     language_id: language_id.to_owned(),
-                    code,
+                    code: code.clone(),
                     watch_submission: false,
                     cookie_storage: (),
                     timeout,
@@ -322,6 +413,22 @@ pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Res
             }
         };
 
+        // The submission has been made. Failing to record it is not an error.
+        if let Err(err) = crate::submission_history::append(
+            &crate::submission_history::history_path(&cargo_compete_config_path),
+            &Record::new(
+                member,
+                bin,
+                &package_metadata_bin.alias,
+                &package_metadata_bin.problem,
+                language_id,
+                &outcome.submission_url,
+                &code,
+            ),
+        ) {
+            shell.warn(format!("could not record the submission: {err}"))?;
+        }
+
         print_status(
             shell,
             &[
@@ -361,7 +468,21 @@ pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Res
                     })?;
                 }
                 PlatformKind::Codeforces => {
-                    shell.warn("watching submissions for Codeforces is not implemented")?;
+                    let (api_key, api_secret) = credentials::codeforces_api_key_and_secret(shell)?;
+                    let handle = credentials::codeforces_handle(shell, |handle| {
+                        crate::web::codeforces::check_handle(&api_key, &api_secret, handle)
+                    })?;
+                    let (contest_id, submission_id) =
+                        crate::web::codeforces::parse_submission_url(&outcome.submission_url)?;
+
+                    crate::web::codeforces::watch_submissions(
+                        &api_key,
+                        &api_secret,
+                        &handle,
+                        contest_id,
+                        Some(submission_id),
+                        shell,
+                    )?;
                 }
                 PlatformKind::Yukicoder => {
                     shell.warn("watching submissions for yukicoder is not implemented")?;
@@ -397,6 +518,22 @@ pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Res
             shell,
         )?;
 
+        // The submission has been made. Failing to record it is not an error.
+        if let Err(err) = crate::submission_history::append(
+            &crate::submission_history::history_path(&cargo_compete_config_path),
+            &Record::new(
+                member,
+                bin,
+                &package_metadata_bin.alias,
+                &package_metadata_bin.problem,
+                &language_id,
+                &url,
+                &code,
+            ),
+        ) {
+            shell.warn(format!("could not record the submission: {err}"))?;
+        }
+
         print_status(
             shell,
             &[
@@ -412,20 +549,28 @@ pub(crate) fn run(opt: OptCompeteSubmit, ctx: crate::Context<'_>) -> anyhow::Res
     Ok(())
 }
 
+fn default_language_id(platform: PlatformKind) -> &'static str {
+    match platform {
+        PlatformKind::Atcoder => ATCODER_RUST_LANG_ID,
+        PlatformKind::Codeforces => CODEFORCES_RUST_LANG_ID,
+        PlatformKind::Yukicoder => YUKICODER_RUST_LANG_ID,
+    }
+}
+
+/// Returns the maximum size of the source code in bytes stated by the platform, if any.
+fn default_size_limit(platform: PlatformKind) -> Option<usize> {
+    match platform {
+        // Stated in the rules of AtCoder contests.
+        PlatformKind::Atcoder => Some(512 * 1024),
+        // Stated on the submit page of Codeforces.
+        PlatformKind::Codeforces => Some(64 * 1024),
+        // yukicoder does not state one.
+        PlatformKind::Yukicoder => None,
+    }
+}
+
 fn print_status(shell: &mut Shell, rows: &[Row]) -> io::Result<()> {
-    let mut table = Table::new();
-    *table.get_format() = FormatBuilder::new()
-        .padding(1, 1)
-        .column_separator('│')
-        .borders('│')
-        .separator(LinePosition::Top, LineSeparator::new('─', '┬', '┌', '┐'))
-        .separator(LinePosition::Title, LineSeparator::new('─', '┼', '├', '┤'))
-        .separator(LinePosition::Intern, LineSeparator::new('─', '┼', '├', '┤'))
-        .separator(LinePosition::Bottom, LineSeparator::new('─', '┴', '└', '┘'))
-        .build();
-    table.extend(rows.iter().cloned());
-    write!(shell.err(), "{table}")?;
-    shell.err().flush()?;
+    shell.print_table(rows)?;
     shell.status("Successfully", "submitted the code")
 }
 
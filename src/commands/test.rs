@@ -1,36 +1,127 @@
 use crate::{
-    config::CargoCompeteConfigTestProfile,
+    config::{CargoCompeteConfig, CargoCompeteConfigTestProfile},
     project::{MetadataExt as _, PackageExt as _},
-    shell::ColorChoice,
+    shell::{ColorChoice, Shell},
+    testing::{
+        filter::TestCaseFilter,
+        history::{self, Baseline},
+        report::{Report, ReportFormat, ReportSuite},
+    },
 };
+use anyhow::bail;
 use human_size::Size;
-use std::path::PathBuf;
+use prettytable::row;
+use std::{num::NonZeroUsize, path::PathBuf};
 use structopt::StructOpt;
 use strum::VariantNames as _;
 
 #[derive(StructOpt, Debug)]
 #[structopt(usage(
     r"cargo compete test [OPTIONS] <bin-name-or-alias>
-    cargo compete test [OPTIONS] --src <PATH>",
+    cargo compete test [OPTIONS] --src <PATH>
+    cargo compete test [OPTIONS] --all
+    cargo compete test [OPTIONS] --workspace",
 ))]
 pub struct OptCompeteTest {
     /// Path to the source code
     #[structopt(
         long,
         value_name("PATH"),
-        required_unless("name-or-alias"),
-        conflicts_with("name-or-alias")
+        required_unless_one(&["name-or-alias", "all", "workspace"]),
+        conflicts_with_all(&["name-or-alias", "all", "workspace"])
     )]
     pub src: Option<PathBuf>,
 
-    /// Test for only the test cases
-    #[structopt(long, value_name("NAME"))]
+    /// Test for only the test cases matching the patterns. Globs, or regexes surrounded by `/`
+    #[structopt(long, value_name("PATTERN"))]
     pub testcases: Option<Vec<String>>,
 
+    /// Skip the test cases matching the patterns. Globs, or regexes surrounded by `/`
+    #[structopt(long, value_name("PATTERN"))]
+    pub skip: Vec<String>,
+
+    /// Stop after the first failure
+    #[structopt(long)]
+    pub fail_fast: bool,
+
+    /// Test all of the `bin`/`example` targets in `package.metadata.cargo-compete`
+    #[structopt(long, conflicts_with_all(&["name-or-alias", "workspace", "watch"]))]
+    pub all: bool,
+
+    /// Test all of the `bin`/`example` targets in all of the workspace members
+    #[structopt(long, conflicts_with_all(&["name-or-alias", "package", "watch"]))]
+    pub workspace: bool,
+
+    /// Write the outputs of `--reference` as the expected outputs of the test cases without them
+    #[structopt(
+        long,
+        requires("reference"),
+        conflicts_with_all(&["all", "workspace", "watch"])
+    )]
+    pub bless: bool,
+
+    /// Name or alias for a `bin`/`example` of a reference solution for `--bless`
+    #[structopt(long, value_name("NAME_OR_ALIAS"), requires("bless"))]
+    pub reference: Option<String>,
+
+    /// Overwrite existing expected outputs with `--bless`
+    #[structopt(long, requires("bless"))]
+    pub force: bool,
+
+    /// Test the code to submit, which `submit.kind` in compete.toml produces, as a standalone crate
+    #[structopt(long, conflicts_with_all(&["all", "workspace", "watch"]))]
+    pub submission_payload: bool,
+
+    /// Rerun the tests every time the source code or the test suite changes
+    #[structopt(long)]
+    pub watch: bool,
+
+    /// Number of test cases to run in parallel. Overrides `test.jobs` in compete.toml
+    #[structopt(short, long, value_name("N"))]
+    pub jobs: Option<NonZeroUsize>,
+
+    /// Run the test cases N times and show the statistics of the elapsed time
+    #[structopt(long, value_name("N"), default_value("1"))]
+    pub repeat: NonZeroUsize,
+
+    /// With `--repeat`, warn about test cases whose median elapsed time exceeds the ratio to the
+    /// timelimit
+    #[structopt(long, value_name("RATIO"), default_value("0.8"))]
+    pub tle_risk: f64,
+
+    /// Compare the elapsed times with the timing history
+    #[structopt(long)]
+    pub compare: bool,
+
+    /// Run to compare with
+    #[structopt(
+        long,
+        value_name("BASELINE"),
+        possible_values(Baseline::VARIANTS),
+        default_value("previous")
+    )]
+    pub baseline: Baseline,
+
+    /// Memory limit. Overrides `memorylimit` in the test suite
+    #[structopt(long, value_name("SIZE"))]
+    pub memory_limit: Option<Size>,
+
     /// Display limit
     #[structopt(long, value_name("SIZE"), default_value("4KiB"))]
     pub display_limit: Size,
 
+    /// Show where the outputs differ for wrong answers. Also enabled by `test.diff` in compete.toml
+    #[structopt(long)]
+    pub diff: bool,
+
+    /// Write a report of the results in the format
+    #[structopt(long, value_name("FORMAT"), possible_values(ReportFormat::VARIANTS))]
+    pub report_format: Option<ReportFormat>,
+
+    /// Path to write the report to. Defaults to the standard output
+    #[structopt(long, value_name("PATH"), requires("report-format"))]
+    pub report_path: Option<PathBuf>,
+
     /// Existing package to retrieving test cases for
     #[structopt(short, long, value_name("SPEC"))]
     pub package: Option<String>,
@@ -56,7 +147,7 @@ pub struct OptCompeteTest {
     )]
     pub color: ColorChoice,
 
-    #[structopt(required_unless("src"))]
+    #[structopt(required_unless_one(&["src", "all", "workspace"]))]
     /// Name or alias for a `bin`/`example`
     pub name_or_alias: Option<String>,
 }
@@ -65,7 +156,25 @@ pub(crate) fn run(opt: OptCompeteTest, ctx: crate::Context<'_>) -> anyhow::Resul
     let OptCompeteTest {
         src,
         testcases,
+        skip,
+        fail_fast,
+        all,
+        workspace,
+        bless,
+        reference,
+        force,
+        submission_payload,
+        watch,
+        jobs,
+        repeat,
+        tle_risk,
+        compare,
+        baseline,
+        memory_limit,
         display_limit,
+        diff,
+        report_format,
+        report_path,
         package,
         debug,
         release,
@@ -78,6 +187,7 @@ pub(crate) fn run(opt: OptCompeteTest, ctx: crate::Context<'_>) -> anyhow::Resul
         cwd,
         cookies_path,
         shell,
+        cargo_compete_exe,
     } = ctx;
 
     shell.set_color_choice(color);
@@ -86,9 +196,182 @@ pub(crate) fn run(opt: OptCompeteTest, ctx: crate::Context<'_>) -> anyhow::Resul
         .map(|p| Ok(cwd.join(p.strip_prefix(".").unwrap_or(&p))))
         .unwrap_or_else(|| crate::project::locate_project(&cwd))?;
     let metadata = crate::project::cargo_metadata(manifest_path, &cwd)?;
+
+    let test_case_filter = TestCaseFilter::new(testcases.as_deref(), &skip)?;
+
+    let history_path = history::history_path(&metadata);
+    let compare = compare.then_some(baseline);
+
+    let report_path = report_path.map(|p| cwd.join(p.strip_prefix(".").unwrap_or(&p)));
+
+    let write_report = |suites: Vec<ReportSuite>, shell: &mut Shell| -> anyhow::Result<()> {
+        if let Some(report_format) = report_format {
+            Report::new(suites).write(report_format, report_path.as_deref(), shell)?;
+        }
+        Ok(())
+    };
+
+    let is_release = |cargo_compete_config: &CargoCompeteConfig| -> bool {
+        if debug {
+            false
+        } else if release {
+            true
+        } else {
+            cargo_compete_config.test.profile == CargoCompeteConfigTestProfile::Release
+        }
+    };
+
+    if all || workspace {
+        let members = if workspace {
+            metadata.all_members()
+        } else {
+            vec![metadata.query_for_member(package.as_deref())?]
+        };
+
+        let mut suites = vec![];
+        let mut summary = vec![row!["Package", "Problem", "Bin", "Result"]];
+        let mut num_failures = 0;
+
+        for member in members {
+            let package_metadata = member.read_package_metadata(shell)?;
+            let (cargo_compete_config, _) = crate::config::load_for_package(member, shell)?;
+
+            let bins = package_metadata
+                .bin
+                .iter()
+                .chain(&package_metadata.example)
+                .map(|(name, pkg_md_bin_example)| {
+                    Ok((member.bin_like_target_by_name(name)?, pkg_md_bin_example))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+
+            if bins.is_empty() {
+                continue;
+            }
+
+            // Build all of the targets in the package at once.
+            let artifacts = match crate::testing::build_all(
+                &metadata,
+                member,
+                &bins.iter().map(|&(bin, _)| bin).collect::<Vec<_>>(),
+                cargo_compete_config.test.toolchain.as_deref(),
+                is_release(&cargo_compete_config),
+                shell,
+            ) {
+                Ok(artifacts) => artifacts,
+                Err(err) => {
+                    crate::testing::check_ctrl_c()?;
+                    shell.error(&err)?;
+                    writeln!(shell.err())?;
+                    for (bin, pkg_md_bin_example) in bins {
+                        summary.push(row![
+                            member.name,
+                            pkg_md_bin_example.alias,
+                            bin.name,
+                            "ERROR",
+                        ]);
+                        num_failures += 1;
+                    }
+                    continue;
+                }
+            };
+
+            for ((bin, pkg_md_bin_example), artifact) in bins.into_iter().zip(&artifacts) {
+                shell.status(
+                    "Testing",
+                    format!("{} ({})", pkg_md_bin_example.alias, member.name),
+                )?;
+
+                let result = crate::testing::test(crate::testing::Args {
+                    metadata: &metadata,
+                    member,
+                    bin,
+                    bin_alias: &pkg_md_bin_example.alias,
+                    cargo_compete_config_test_suite: &cargo_compete_config.test_suite,
+                    problem_url: &pkg_md_bin_example.problem,
+                    toolchain: cargo_compete_config.test.toolchain.as_deref(),
+                    interactor: cargo_compete_config.test.interactor.as_deref(),
+                    checker: cargo_compete_config.test.checker.as_ref(),
+                    run: &cargo_compete_config.test.run,
+                    release: is_release(&cargo_compete_config),
+                    memory_limit,
+                    jobs: jobs
+                        .or(cargo_compete_config.test.jobs)
+                        .unwrap_or_else(crate::testing::default_jobs),
+                    repeat,
+                    tle_risk,
+                    test_case_filter: &test_case_filter,
+                    fail_fast,
+                    display_limit,
+                    diff: diff || cargo_compete_config.test.diff,
+                    artifact: Some(artifact),
+                    cookies_path: &cookies_path,
+                    cargo_compete_exe: cargo_compete_exe.as_deref(),
+                    shell,
+                });
+
+                let result = result.and_then(|suite| {
+                    history::record(&history_path, bin, &suite, compare, shell)?;
+                    Ok(suite)
+                });
+
+                let result = match result {
+                    Ok(suite) => {
+                        if suite.num_failures() > 0 {
+                            num_failures += 1;
+                        }
+                        let num_passed = suite.cases.len() - suite.num_failures();
+                        let result = format!(
+                            "{} ({}/{} passed)",
+                            if suite.num_failures() == 0 {
+                                "passed"
+                            } else {
+                                "FAILED"
+                            },
+                            num_passed,
+                            suite.cases.len(),
+                        );
+                        suites.push(suite);
+                        result
+                    }
+                    Err(err) => {
+                        crate::testing::check_ctrl_c()?;
+                        shell.error(&err)?;
+                        num_failures += 1;
+                        "ERROR".to_owned()
+                    }
+                };
+                writeln!(shell.err())?;
+
+                summary.push(row![
+                    member.name,
+                    pkg_md_bin_example.alias,
+                    bin.name,
+                    result,
+                ]);
+            }
+        }
+
+        let num_problems = summary.len() - 1;
+
+        shell.print_table(&summary)?;
+        write_report(suites, shell)?;
+
+        if num_failures > 0 {
+            bail!(
+                "{}/{} problem{} failed",
+                num_failures,
+                num_problems,
+                if num_failures == 1 { "" } else { "s" },
+            );
+        }
+        return Ok(());
+    }
+
     let member = metadata.query_for_member(package.as_deref())?;
     let package_metadata = member.read_package_metadata(shell)?;
-    let (cargo_compete_config, _) = crate::config::load_for_package(member, shell)?;
+    let (cargo_compete_config, cargo_compete_config_path) =
+        crate::config::load_for_package(member, shell)?;
 
     let (bin, pkg_md_bin_example) = if let Some(src) = src {
         let src = cwd.join(src.strip_prefix(".").unwrap_or(&src));
@@ -104,7 +387,54 @@ pub(crate) fn run(opt: OptCompeteTest, ctx: crate::Context<'_>) -> anyhow::Resul
         unreachable!()
     };
 
-    crate::testing::test(crate::testing::Args {
+    if bless {
+        let reference = member.bin_like_target_by_name_or_alias(
+            &package_metadata,
+            reference
+                .as_deref()
+                .expect("`--bless` requires `--reference`"),
+        )?;
+
+        crate::testing::bless::bless(crate::testing::bless::Args {
+            metadata: &metadata,
+            member,
+            bin,
+            bin_alias: &pkg_md_bin_example.alias,
+            reference,
+            cargo_compete_config_test_suite: &cargo_compete_config.test_suite,
+            problem_url: &pkg_md_bin_example.problem,
+            toolchain: cargo_compete_config.test.toolchain.as_deref(),
+            release: is_release(&cargo_compete_config),
+            force,
+            shell,
+        })?;
+    }
+
+    let artifact = if submission_payload {
+        let payload = crate::testing::payload::payload(
+            &cargo_compete_config.submit,
+            &metadata,
+            member,
+            bin,
+            &pkg_md_bin_example.alias,
+            &pkg_md_bin_example.problem,
+            shell,
+        )?;
+        Some(crate::testing::payload::build(
+            &metadata,
+            member,
+            bin,
+            &payload,
+            &cargo_compete_config,
+            &cargo_compete_config_path,
+            is_release(&cargo_compete_config),
+            shell,
+        )?)
+    } else {
+        None
+    };
+
+    let args = crate::testing::Args {
         metadata: &metadata,
         member,
         bin,
@@ -112,16 +442,37 @@ pub(crate) fn run(opt: OptCompeteTest, ctx: crate::Context<'_>) -> anyhow::Resul
         cargo_compete_config_test_suite: &cargo_compete_config.test_suite,
         problem_url: &pkg_md_bin_example.problem,
         toolchain: cargo_compete_config.test.toolchain.as_deref(),
-        release: if debug {
-            false
-        } else if release {
-            true
-        } else {
-            cargo_compete_config.test.profile == CargoCompeteConfigTestProfile::Release
-        },
-        test_case_names: testcases.map(|ss| ss.into_iter().collect()),
+        interactor: cargo_compete_config.test.interactor.as_deref(),
+        checker: cargo_compete_config.test.checker.as_ref(),
+        run: &cargo_compete_config.test.run,
+        release: is_release(&cargo_compete_config),
+        memory_limit,
+        jobs: jobs
+            .or(cargo_compete_config.test.jobs)
+            .unwrap_or_else(crate::testing::default_jobs),
+        repeat,
+        tle_risk,
+        test_case_filter: &test_case_filter,
+        fail_fast,
         display_limit,
+        diff: diff || cargo_compete_config.test.diff,
+        artifact: artifact.as_deref(),
         cookies_path: &cookies_path,
-        shell,
-    })
+        cargo_compete_exe: cargo_compete_exe.as_deref(),
+        shell: &mut *shell,
+    };
+
+    let on_finish = |suite: ReportSuite, shell: &mut Shell| -> anyhow::Result<()> {
+        let result = suite.error_on_fail();
+        history::record(&history_path, bin, &suite, compare, shell)?;
+        write_report(vec![suite], shell)?;
+        result
+    };
+
+    if watch {
+        crate::testing::watch(args, on_finish)
+    } else {
+        let suite = crate::testing::test(args)?;
+        on_finish(suite, shell)
+    }
 }
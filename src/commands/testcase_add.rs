@@ -0,0 +1,163 @@
+use crate::{
+    config::CargoCompeteConfigTestProfile,
+    project::{MetadataExt as _, PackageExt as _},
+    shell::ColorChoice,
+    testing::testcase::Output,
+};
+use std::path::PathBuf;
+use structopt::StructOpt;
+use strum::VariantNames as _;
+
+#[derive(StructOpt, Debug)]
+pub struct OptCompeteTestcaseAdd {
+    /// Name of the test case. Defaults to `handmade<N>`
+    #[structopt(long, value_name("NAME"))]
+    pub name: Option<String>,
+
+    /// Path to the input. Defaults to the standard input
+    #[structopt(long, value_name("PATH"), conflicts_with("input-text"))]
+    pub input: Option<PathBuf>,
+
+    /// Input text. A newline is appended if it does not end with one
+    #[structopt(long, value_name("TEXT"))]
+    pub input_text: Option<String>,
+
+    /// Path to the expected output
+    #[structopt(
+        long,
+        value_name("PATH"),
+        conflicts_with_all(&["output-text", "reference"])
+    )]
+    pub output: Option<PathBuf>,
+
+    /// Expected output text. A newline is appended if it does not end with one
+    #[structopt(long, value_name("TEXT"), conflicts_with("reference"))]
+    pub output_text: Option<String>,
+
+    /// Name or alias for a `bin`/`example` to compute the expected output with
+    #[structopt(long, value_name("NAME"))]
+    pub reference: Option<String>,
+
+    /// Existing package
+    #[structopt(short, long, value_name("SPEC"))]
+    pub package: Option<String>,
+
+    /// Build in debug mode. Overrides `test.profile` in compete.toml
+    #[structopt(long, conflicts_with("release"))]
+    pub debug: bool,
+
+    /// Build in release mode. Overrides `test.profile` in compete.toml
+    #[structopt(long)]
+    pub release: bool,
+
+    /// Path to Cargo.toml
+    #[structopt(long, value_name("PATH"))]
+    pub manifest_path: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        value_name("WHEN"),
+        possible_values(ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: ColorChoice,
+
+    /// Name or alias for a `bin`/`example`
+    pub name_or_alias: String,
+}
+
+pub(crate) fn run(opt: OptCompeteTestcaseAdd, ctx: crate::Context<'_>) -> anyhow::Result<()> {
+    let OptCompeteTestcaseAdd {
+        name,
+        input,
+        input_text,
+        output,
+        output_text,
+        reference,
+        package,
+        debug,
+        release,
+        manifest_path,
+        color,
+        name_or_alias,
+    } = opt;
+
+    let crate::Context { cwd, shell, .. } = ctx;
+
+    shell.set_color_choice(color);
+
+    let manifest_path = manifest_path
+        .map(|p| Ok(cwd.join(p.strip_prefix(".").unwrap_or(&p))))
+        .unwrap_or_else(|| crate::project::locate_project(&cwd))?;
+    let metadata = crate::project::cargo_metadata(manifest_path, &cwd)?;
+    let member = metadata.query_for_member(package.as_deref())?;
+    let package_metadata = member.read_package_metadata(shell)?;
+    let (cargo_compete_config, _) = crate::config::load_for_package(member, shell)?;
+
+    let (bin_name, pkg_md_bin_example) =
+        package_metadata.bin_like_by_name_or_alias(&name_or_alias)?;
+
+    let reference = reference
+        .map(|r| member.bin_like_target_by_name_or_alias(&package_metadata, &r))
+        .transpose()?;
+
+    let test_suite_path = crate::testing::test_suite_path(
+        &metadata.workspace_root,
+        member.manifest_dir(),
+        &cargo_compete_config.test_suite,
+        bin_name,
+        &pkg_md_bin_example.alias,
+        &pkg_md_bin_example.problem,
+        shell,
+    )?;
+
+    let read = |path: PathBuf| crate::fs::read_to_string(cwd.join(path));
+    let with_newline = |mut text: String| {
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+        text
+    };
+
+    let input = if let Some(input) = input {
+        read(input)?
+    } else if let Some(input_text) = input_text {
+        with_newline(input_text)
+    } else {
+        shell.read_to_string()?
+    };
+
+    let output_text = if let Some(output) = output {
+        Some(read(output)?)
+    } else {
+        output_text.map(with_newline)
+    };
+
+    let output = if let Some(output_text) = &output_text {
+        Output::Text(output_text)
+    } else if let Some(reference) = reference {
+        Output::Reference(reference)
+    } else {
+        Output::None
+    };
+
+    crate::testing::testcase::add(crate::testing::testcase::AddArgs {
+        metadata: &metadata,
+        member,
+        bin: member.bin_like_target_by_name(bin_name)?,
+        test_suite_path: &test_suite_path,
+        name: name.as_deref(),
+        input: &input,
+        output,
+        toolchain: cargo_compete_config.test.toolchain.as_deref(),
+        release: if debug {
+            false
+        } else if release {
+            true
+        } else {
+            cargo_compete_config.test.profile == CargoCompeteConfigTestProfile::Release
+        },
+        shell,
+    })
+}
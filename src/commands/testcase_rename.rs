@@ -0,0 +1,76 @@
+use crate::{
+    project::{MetadataExt as _, PackageExt as _},
+    shell::ColorChoice,
+};
+use std::path::PathBuf;
+use structopt::StructOpt;
+use strum::VariantNames as _;
+
+#[derive(StructOpt, Debug)]
+pub struct OptCompeteTestcaseRename {
+    /// Current name of the test case
+    #[structopt(long, value_name("NAME"))]
+    pub testcase: String,
+
+    /// New name of the test case
+    #[structopt(long, value_name("NAME"))]
+    pub to: String,
+
+    /// Existing package
+    #[structopt(short, long, value_name("SPEC"))]
+    pub package: Option<String>,
+
+    /// Path to Cargo.toml
+    #[structopt(long, value_name("PATH"))]
+    pub manifest_path: Option<PathBuf>,
+
+    /// Coloring
+    #[structopt(
+        long,
+        value_name("WHEN"),
+        possible_values(ColorChoice::VARIANTS),
+        default_value("auto")
+    )]
+    pub color: ColorChoice,
+
+    /// Name or alias for a `bin`/`example`
+    pub name_or_alias: String,
+}
+
+pub(crate) fn run(opt: OptCompeteTestcaseRename, ctx: crate::Context<'_>) -> anyhow::Result<()> {
+    let OptCompeteTestcaseRename {
+        testcase,
+        to,
+        package,
+        manifest_path,
+        color,
+        name_or_alias,
+    } = opt;
+
+    let crate::Context { cwd, shell, .. } = ctx;
+
+    shell.set_color_choice(color);
+
+    let manifest_path = manifest_path
+        .map(|p| Ok(cwd.join(p.strip_prefix(".").unwrap_or(&p))))
+        .unwrap_or_else(|| crate::project::locate_project(&cwd))?;
+    let metadata = crate::project::cargo_metadata(manifest_path, &cwd)?;
+    let member = metadata.query_for_member(package.as_deref())?;
+    let package_metadata = member.read_package_metadata(shell)?;
+    let (cargo_compete_config, _) = crate::config::load_for_package(member, shell)?;
+
+    let (bin_name, pkg_md_bin_example) =
+        package_metadata.bin_like_by_name_or_alias(&name_or_alias)?;
+
+    let test_suite_path = crate::testing::test_suite_path(
+        &metadata.workspace_root,
+        member.manifest_dir(),
+        &cargo_compete_config.test_suite,
+        bin_name,
+        &pkg_md_bin_example.alias,
+        &pkg_md_bin_example.problem,
+        shell,
+    )?;
+
+    crate::testing::testcase::rename(&test_suite_path, &testcase, &to, shell)
+}
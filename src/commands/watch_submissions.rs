@@ -1,4 +1,5 @@
 use crate::{shell::ColorChoice, web::credentials};
+use anyhow::Context as _;
 use snowchains_core::web::{
     Atcoder, AtcoderWatchSubmissionsCredentials, AtcoderWatchSubmissionsTarget, CookieStorage,
     PlatformKind, WatchSubmissions,
@@ -19,7 +20,7 @@ pub struct OptCompeteWatchSubmissions {
     pub color: ColorChoice,
 
     /// Platform
-    #[structopt(possible_value("atcoder"))]
+    #[structopt(possible_values(&["atcoder", "codeforces"]))]
     pub platform: PlatformKind,
 
     /// Contest ID
@@ -37,6 +38,7 @@ pub(crate) fn run(opt: OptCompeteWatchSubmissions, ctx: crate::Context<'_>) -> a
         cwd: _,
         cookies_path,
         shell,
+        cargo_compete_exe: _,
     } = ctx;
 
     shell.set_color_choice(color);
@@ -44,25 +46,44 @@ pub(crate) fn run(opt: OptCompeteWatchSubmissions, ctx: crate::Context<'_>) -> a
     let cookie_storage = CookieStorage::with_jsonl(cookies_path)?;
     let timeout = crate::web::TIMEOUT;
 
-    if platform == PlatformKind::Atcoder {
-        let shell = RefCell::new(shell);
+    match platform {
+        PlatformKind::Atcoder => {
+            let shell = RefCell::new(shell);
 
-        let credentials = AtcoderWatchSubmissionsCredentials {
-            username_and_password: &mut credentials::username_and_password(
-                &shell,
-                "Username: ",
-                "Password: ",
-            ),
-        };
+            let credentials = AtcoderWatchSubmissionsCredentials {
+                username_and_password: &mut credentials::username_and_password(
+                    &shell,
+                    "Username: ",
+                    "Password: ",
+                ),
+            };
 
-        Atcoder::exec(WatchSubmissions {
-            target: AtcoderWatchSubmissionsTarget { contest },
-            credentials,
-            cookie_storage,
-            timeout,
-            shell: &shell,
-        })
-    } else {
-        unreachable!()
+            Atcoder::exec(WatchSubmissions {
+                target: AtcoderWatchSubmissionsTarget { contest },
+                credentials,
+                cookie_storage,
+                timeout,
+                shell: &shell,
+            })
+        }
+        PlatformKind::Codeforces => {
+            let contest_id = contest.parse().with_context(|| {
+                format!("a contest ID for Codeforces must be an unsigned integer: {contest:?}")
+            })?;
+            let (api_key, api_secret) = credentials::codeforces_api_key_and_secret(shell)?;
+            let handle = credentials::codeforces_handle(shell, |handle| {
+                crate::web::codeforces::check_handle(&api_key, &api_secret, handle)
+            })?;
+
+            crate::web::codeforces::watch_submissions(
+                &api_key,
+                &api_secret,
+                &handle,
+                contest_id,
+                None,
+                shell,
+            )
+        }
+        PlatformKind::Yukicoder => unreachable!(),
     }
 }
@@ -12,6 +12,7 @@ use snowchains_core::web::PlatformKind;
 use std::{
     collections::BTreeMap,
     fmt::{self, Display},
+    num::NonZeroUsize,
     path::Path,
     str::{self, FromStr},
 };
@@ -530,12 +531,50 @@ impl Default for BinLikeTargetKind {
     }
 }
 
-#[derive(Deserialize, Default, Debug)]
+#[derive(Deserialize, Default, Derivative)]
+#[derivative(Debug)]
 #[serde(rename_all = "kebab-case")]
 pub(crate) struct CargoCompeteConfigTest {
     pub(crate) toolchain: Option<String>,
     #[serde(default)]
     pub(crate) profile: CargoCompeteConfigTestProfile,
+    pub(crate) jobs: Option<NonZeroUsize>,
+    #[derivative(Debug = "ignore")]
+    #[serde(default, deserialize_with = "deserialize_option_liquid_templates")]
+    pub(crate) interactor: Option<Vec<liquid::Template>>,
+    pub(crate) checker: Option<CargoCompeteConfigTestChecker>,
+    #[serde(default)]
+    pub(crate) diff: bool,
+    #[serde(flatten)]
+    pub(crate) run: CargoCompeteConfigTestRun,
+}
+
+/// How to run the artifact in the test.
+#[derive(Deserialize, Default, Derivative)]
+#[derivative(Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct CargoCompeteConfigTestRun {
+    #[derivative(Debug = "ignore")]
+    #[serde(default, deserialize_with = "deserialize_option_liquid_templates")]
+    pub(crate) cmd: Option<Vec<liquid::Template>>,
+    #[serde(default)]
+    pub(crate) env: BTreeMap<String, String>,
+    #[derivative(Debug = "ignore")]
+    #[serde(default, deserialize_with = "deserialize_option_liquid_template")]
+    pub(crate) cwd: Option<liquid::Template>,
+    #[serde(default, deserialize_with = "deser_option_fromstr")]
+    pub(crate) stack_size: Option<human_size::Size>,
+}
+
+#[derive(Deserialize, Derivative)]
+#[derivative(Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct CargoCompeteConfigTestChecker {
+    #[derivative(Debug = "ignore")]
+    #[serde(deserialize_with = "deserialize_liquid_templates")]
+    pub(crate) cmd: Vec<liquid::Template>,
+    #[serde(default)]
+    pub(crate) testlib: bool,
 }
 
 #[derive(Deserialize, Debug, Copy, Clone, PartialEq)]
@@ -555,6 +594,7 @@ impl Default for CargoCompeteConfigTestProfile {
 pub(crate) enum CargoCompeteConfigSubmit {
     File(CargoCompeteConfigSubmitFile),
     Command(CargoCompeteConfigSubmitCommand),
+    Bundle(CargoCompeteConfigSubmitBundle),
     DeprecatedTranspileCommand(CargoCompeteConfigSubmitCommand),
 }
 
@@ -564,6 +604,7 @@ pub(crate) struct CargoCompeteConfigSubmitFile {
     #[derivative(Debug = "ignore")]
     pub(crate) path: liquid::Template,
     pub(crate) language_id: Option<String>,
+    pub(crate) size_limit: Option<human_size::Size>,
 }
 
 #[derive(Derivative)]
@@ -572,6 +613,13 @@ pub(crate) struct CargoCompeteConfigSubmitCommand {
     #[derivative(Debug = "ignore")]
     pub(crate) args: Vec<liquid::Template>,
     pub(crate) language_id: Option<String>,
+    pub(crate) size_limit: Option<human_size::Size>,
+}
+
+#[derive(Debug)]
+pub(crate) struct CargoCompeteConfigSubmitBundle {
+    pub(crate) language_id: Option<String>,
+    pub(crate) size_limit: Option<human_size::Size>,
 }
 
 impl Default for CargoCompeteConfigSubmit {
@@ -583,6 +631,7 @@ impl Default for CargoCompeteConfigSubmit {
                 .parse("{{ src_path }}")
                 .unwrap(),
             language_id: None,
+            size_limit: None,
         })
     }
 }
@@ -594,17 +643,37 @@ impl<'de> Deserialize<'de> for CargoCompeteConfigSubmit {
     {
         let repr = Repr::deserialize(deserializer)?;
         return Ok(match repr {
-            Repr::CurrentForm(CurrentForm::File { path, language_id }) => {
-                Self::File(CargoCompeteConfigSubmitFile { path, language_id })
-            }
-            Repr::CurrentForm(CurrentForm::Command { args, language_id }) => {
-                Self::Command(CargoCompeteConfigSubmitCommand { args, language_id })
-            }
+            Repr::CurrentForm(CurrentForm::File {
+                path,
+                language_id,
+                size_limit,
+            }) => Self::File(CargoCompeteConfigSubmitFile {
+                path,
+                language_id,
+                size_limit,
+            }),
+            Repr::CurrentForm(CurrentForm::Command {
+                args,
+                language_id,
+                size_limit,
+            }) => Self::Command(CargoCompeteConfigSubmitCommand {
+                args,
+                language_id,
+                size_limit,
+            }),
+            Repr::CurrentForm(CurrentForm::Bundle {
+                language_id,
+                size_limit,
+            }) => Self::Bundle(CargoCompeteConfigSubmitBundle {
+                language_id,
+                size_limit,
+            }),
             Repr::Deprecated(Deprecated {
                 transpile: DeprecatedSubmit::Command { args, language_id },
             }) => Self::DeprecatedTranspileCommand(CargoCompeteConfigSubmitCommand {
                 args,
                 language_id,
+                size_limit: None,
             }),
         });
 
@@ -622,11 +691,20 @@ impl<'de> Deserialize<'de> for CargoCompeteConfigSubmit {
                 #[serde(deserialize_with = "deserialize_liquid_template")]
                 path: liquid::Template,
                 language_id: Option<String>,
+                #[serde(default, deserialize_with = "deser_option_fromstr")]
+                size_limit: Option<human_size::Size>,
             },
             Command {
                 #[serde(deserialize_with = "deserialize_liquid_templates")]
                 args: Vec<liquid::Template>,
                 language_id: Option<String>,
+                #[serde(default, deserialize_with = "deser_option_fromstr")]
+                size_limit: Option<human_size::Size>,
+            },
+            Bundle {
+                language_id: Option<String>,
+                #[serde(default, deserialize_with = "deser_option_fromstr")]
+                size_limit: Option<human_size::Size>,
             },
         }
 
@@ -678,6 +756,29 @@ where
         .map_err(D::Error::custom)
 }
 
+fn deserialize_option_liquid_templates<'de, D>(
+    deserializer: D,
+) -> Result<Option<Vec<liquid::Template>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_liquid_templates(deserializer).map(Some)
+}
+
+fn deserialize_option_liquid_template<'de, D>(
+    deserializer: D,
+) -> Result<Option<liquid::Template>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    liquid::ParserBuilder::with_stdlib()
+        .build()
+        .map_err(D::Error::custom)?
+        .parse(&String::deserialize(deserializer)?)
+        .map(Some)
+        .map_err(D::Error::custom)
+}
+
 fn deserialize_liquid_template_with_custom_filter<'de, D>(
     deserializer: D,
 ) -> Result<liquid::Template, D::Error>
@@ -1,6 +1,15 @@
 use anyhow::Context as _;
 use serde::{de::DeserializeOwned, Serialize};
-use std::path::{Path, PathBuf};
+use std::{
+    fs::OpenOptions,
+    io::Write as _,
+    path::{Path, PathBuf},
+};
+
+pub(crate) fn read(path: impl AsRef<Path>) -> anyhow::Result<Vec<u8>> {
+    let path = path.as_ref();
+    std::fs::read(path).with_context(|| format!("could not read `{}`", path.display()))
+}
 
 pub(crate) fn read_to_string(path: impl AsRef<Path>) -> anyhow::Result<String> {
     let path = path.as_ref();
@@ -13,6 +22,25 @@ pub(crate) fn read_json<T: DeserializeOwned, P: AsRef<Path>>(path: P) -> anyhow:
         .with_context(|| format!("could not parse the JSON file at `{}`", path.display()))
 }
 
+/// Reads a JSON Lines file. Returns an empty `Vec` if it does not exist.
+pub(crate) fn read_jsonl<T: DeserializeOwned, P: AsRef<Path>>(path: P) -> anyhow::Result<Vec<T>> {
+    let path = path.as_ref();
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    read_to_string(path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()
+        .with_context(|| {
+            format!(
+                "could not parse the JSON Lines file at `{}`",
+                path.display()
+            )
+        })
+}
+
 pub(crate) fn read_yaml<T: DeserializeOwned, P: AsRef<Path>>(path: P) -> anyhow::Result<T> {
     let path = path.as_ref();
     serde_yaml::from_str(&read_to_string(path)?)
@@ -28,6 +56,21 @@ pub(crate) fn write_json(path: impl AsRef<Path>, content: impl Serialize) -> any
     write(path, serde_json::to_string(&content)?)
 }
 
+/// Appends `content` to a JSON Lines file, creating it and its parent directories if necessary.
+pub(crate) fn append_jsonl(path: impl AsRef<Path>, content: impl Serialize) -> anyhow::Result<()> {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent)?;
+    }
+    let line = serde_json::to_string(&content)? + "\n";
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(line.as_ref()))
+        .with_context(|| format!("could not write `{}`", path.display()))
+}
+
 pub(crate) fn create_dir_all(path: impl AsRef<Path>) -> anyhow::Result<()> {
     let path = path.as_ref();
     std::fs::create_dir_all(path).with_context(|| format!("could not create `{}`", path.display()))
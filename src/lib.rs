@@ -1,6 +1,7 @@
 #![forbid(unsafe_code)]
 #![warn(rust_2018_idioms)]
 
+mod bundle;
 mod commands;
 mod config;
 mod fs;
@@ -9,17 +10,23 @@ mod open;
 mod process;
 mod project;
 pub mod shell;
+mod submission_history;
 mod testing;
 mod web;
 
 use crate::{
     commands::{
-        add::OptCompeteAdd, init::OptCompeteInit, login::OptCompeteLogin,
-        migrate_cargo_atcoder::OptCompeteMigrateCargoAtcoder, new::OptCompeteNew,
-        open::OptCompeteOpen, participate::OptCompeteParticipate,
+        add::OptCompeteAdd, exec_with_stack_size::OptCompeteExecWithStackSize,
+        history_diff::OptCompeteHistoryDiff, history_list::OptCompeteHistoryList,
+        history_show::OptCompeteHistoryShow, init::OptCompeteInit, login::OptCompeteLogin,
+        migrate_cargo_atcoder::OptCompeteMigrateCargoAtcoder, minimize::OptCompeteMinimize,
+        new::OptCompeteNew, open::OptCompeteOpen, participate::OptCompeteParticipate,
         retrieve_submission_summaries::OptCompeteRetrieveSubmissionSummaries,
-        retrieve_testcases::OptCompeteRetrieveTestcases, submit::OptCompeteSubmit,
-        test::OptCompeteTest, watch_submissions::OptCompeteWatchSubmissions,
+        retrieve_testcases::OptCompeteRetrieveTestcases, run::OptCompeteRun,
+        stress::OptCompeteStress, submit::OptCompeteSubmit, test::OptCompeteTest,
+        testcase_add::OptCompeteTestcaseAdd, testcase_list::OptCompeteTestcaseList,
+        testcase_remove::OptCompeteTestcaseRemove, testcase_rename::OptCompeteTestcaseRename,
+        watch_submissions::OptCompeteWatchSubmissions,
     },
     shell::Shell,
 };
@@ -83,9 +90,32 @@ pub enum OptCompete {
     #[structopt(author, visible_alias("t"))]
     Test(OptCompeteTest),
 
+    /// Edit test cases
+    #[structopt(author)]
+    Testcase(OptCompeteTestcase),
+
+    /// Run your code with an input
+    #[structopt(author)]
+    Run(OptCompeteRun),
+
+    /// Test your code with random inputs against a reference solution
+    #[structopt(author)]
+    Stress(OptCompeteStress),
+
+    /// Shrink a failing input
+    #[structopt(author)]
+    Minimize(OptCompeteMinimize),
+
     /// Submit your code
     #[structopt(author, visible_alias("s"))]
     Submit(OptCompeteSubmit),
+
+    /// Look back on your submissions
+    #[structopt(author)]
+    History(OptCompeteHistory),
+
+    #[structopt(author, setting(AppSettings::Hidden))]
+    ExecWithStackSize(OptCompeteExecWithStackSize),
 }
 
 #[derive(StructOpt, Debug)]
@@ -113,10 +143,48 @@ pub enum OptCompeteWatch {
     Submissions(OptCompeteWatchSubmissions),
 }
 
+#[derive(StructOpt, Debug)]
+pub enum OptCompeteTestcase {
+    /// Add a test case
+    #[structopt(author, visible_alias("a"))]
+    Add(OptCompeteTestcaseAdd),
+
+    /// List test cases
+    #[structopt(author, visible_alias("l"))]
+    List(OptCompeteTestcaseList),
+
+    /// Remove a test case
+    #[structopt(author)]
+    Remove(OptCompeteTestcaseRemove),
+
+    /// Rename a test case
+    #[structopt(author)]
+    Rename(OptCompeteTestcaseRename),
+}
+
+#[derive(StructOpt, Debug)]
+pub enum OptCompeteHistory {
+    /// List submissions
+    #[structopt(author, visible_alias("l"))]
+    List(OptCompeteHistoryList),
+
+    /// Show the code of a submission
+    #[structopt(author)]
+    Show(OptCompeteHistoryShow),
+
+    /// Show the difference between the code of two submissions
+    #[structopt(author)]
+    Diff(OptCompeteHistoryDiff),
+}
+
 pub struct Context<'s> {
     pub cwd: PathBuf,
     pub cookies_path: PathBuf,
     pub shell: &'s mut Shell,
+    /// The `cargo-compete` executable to run `cargo compete exec-with-stack-size` with.
+    ///
+    /// If `None`, the artifacts are run without setting the stack size.
+    pub cargo_compete_exe: Option<PathBuf>,
 }
 
 pub fn run(opt: OptCompete, ctx: Context<'_>) -> anyhow::Result<()> {
@@ -140,6 +208,23 @@ pub fn run(opt: OptCompete, ctx: Context<'_>) -> anyhow::Result<()> {
         }
         OptCompete::Open(opt) => commands::open::run(opt, ctx),
         OptCompete::Test(opt) => commands::test::run(opt, ctx),
+        OptCompete::Testcase(OptCompeteTestcase::Add(opt)) => commands::testcase_add::run(opt, ctx),
+        OptCompete::Testcase(OptCompeteTestcase::List(opt)) => {
+            commands::testcase_list::run(opt, ctx)
+        }
+        OptCompete::Testcase(OptCompeteTestcase::Remove(opt)) => {
+            commands::testcase_remove::run(opt, ctx)
+        }
+        OptCompete::Testcase(OptCompeteTestcase::Rename(opt)) => {
+            commands::testcase_rename::run(opt, ctx)
+        }
+        OptCompete::Run(opt) => commands::run::run(opt, ctx),
+        OptCompete::Stress(opt) => commands::stress::run(opt, ctx),
+        OptCompete::Minimize(opt) => commands::minimize::run(opt, ctx),
         OptCompete::Submit(opt) => commands::submit::run(opt, ctx),
+        OptCompete::History(OptCompeteHistory::List(opt)) => commands::history_list::run(opt, ctx),
+        OptCompete::History(OptCompeteHistory::Show(opt)) => commands::history_show::run(opt, ctx),
+        OptCompete::History(OptCompeteHistory::Diff(opt)) => commands::history_diff::run(opt, ctx),
+        OptCompete::ExecWithStackSize(opt) => commands::exec_with_stack_size::run(opt, ctx),
     }
 }
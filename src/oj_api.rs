@@ -224,6 +224,14 @@ pub(crate) struct Problem {
     /// > ```
     pub(crate) time_limit: Option<u64>,
 
+    /// > ```text
+    /// > "memoryLimit": {
+    /// >   "type": "integer",
+    /// >   "description": "in megabytes (MB)"
+    /// > },
+    /// > ```
+    pub(crate) memory_limit: Option<u64>,
+
     /// > ```text
     /// > "tests": {
     /// >   "type": "array",
@@ -6,9 +6,11 @@ use std::{
     env,
     ffi::{OsStr, OsString},
     fmt,
-    io::Write as _,
+    io::{Read as _, Write as _},
     path::{Path, PathBuf},
     process::{ExitStatus, Stdio},
+    thread,
+    time::{Duration, Instant},
 };
 
 #[derive(Debug)]
@@ -18,6 +20,8 @@ pub(crate) struct ProcessBuilder<C: Presence<Utf8PathBuf>> {
     cwd: C::Value,
     display_cwd: bool,
     pipe_input: Option<Vec<u8>>,
+    null_stderr: bool,
+    timeout: Option<Duration>,
 }
 
 impl<C: Presence<Utf8PathBuf>> ProcessBuilder<C> {
@@ -38,6 +42,8 @@ impl<C: Presence<Utf8PathBuf>> ProcessBuilder<C> {
             cwd: cwd.as_ref().to_owned(),
             display_cwd: self.display_cwd,
             pipe_input: self.pipe_input,
+            null_stderr: self.null_stderr,
+            timeout: self.timeout,
         }
     }
 
@@ -48,10 +54,23 @@ impl<C: Presence<Utf8PathBuf>> ProcessBuilder<C> {
         }
     }
 
+    pub(crate) fn null_stderr(self) -> Self {
+        Self {
+            null_stderr: true,
+            ..self
+        }
+    }
+
     pub(crate) fn pipe_input(mut self, pipe_input: Option<impl Into<Vec<u8>>>) -> Self {
         self.pipe_input = pipe_input.map(Into::into);
         self
     }
+
+    /// Makes `read` kill the process and fail if it does not exit within `timeout`.
+    pub(crate) fn timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
 }
 
 impl ProcessBuilder<Present> {
@@ -72,9 +91,37 @@ impl ProcessBuilder<Present> {
         self.spawn(Stdio::inherit())?.wait().map_err(Into::into)
     }
 
-    fn read(&self) -> anyhow::Result<String> {
-        let std::process::Output { status, stdout, .. } =
-            self.spawn(Stdio::piped())?.wait_with_output()?;
+    pub(crate) fn read(&self) -> anyhow::Result<String> {
+        let (status, stdout) = match self.timeout {
+            None => {
+                let std::process::Output { status, stdout, .. } =
+                    self.spawn(Stdio::piped())?.wait_with_output()?;
+                (status, stdout)
+            }
+            Some(timeout) => {
+                let mut child = self.spawn(Stdio::piped())?;
+                let stdout = {
+                    let mut stdout = child.stdout.take().unwrap();
+                    thread::spawn(move || {
+                        let mut buf = vec![];
+                        stdout.read_to_end(&mut buf).map(|_| buf)
+                    })
+                };
+                let deadline = Instant::now() + timeout;
+                let status = loop {
+                    if let Some(status) = child.try_wait()? {
+                        break status;
+                    }
+                    if Instant::now() >= deadline {
+                        let _ = child.kill();
+                        let _ = child.wait();
+                        bail!("{} timed out after {} ms", self, timeout.as_millis());
+                    }
+                    thread::sleep(Duration::from_millis(1));
+                };
+                (status, stdout.join().unwrap()?)
+            }
+        };
         if !status.success() {
             bail!("{} didn't exit successfully: {}", self, status);
         }
@@ -96,12 +143,18 @@ impl ProcessBuilder<Present> {
                 Stdio::inherit()
             })
             .stdout(stdout)
+            .stderr(if self.null_stderr {
+                Stdio::null()
+            } else {
+                Stdio::inherit()
+            })
             .spawn()?;
 
-        if let (Some(mut stdin), Some(pipe_input)) = (child.stdin.take(), self.pipe_input.as_ref())
-        {
-            stdin.write_all(pipe_input)?;
-            stdin.flush()?;
+        // Write in another thread so that the child does not block on a full stdout pipe.
+        if let (Some(mut stdin), Some(pipe_input)) = (child.stdin.take(), self.pipe_input.clone()) {
+            thread::spawn(move || {
+                let _ = stdin.write_all(&pipe_input).and_then(|()| stdin.flush());
+            });
         }
 
         Ok(child)
@@ -152,6 +205,8 @@ pub(crate) fn process(program: impl AsRef<Path>) -> ProcessBuilder<NotPresent> {
         cwd: (),
         display_cwd: false,
         pipe_input: None,
+        null_stderr: false,
+        timeout: None,
     }
 }
 
@@ -168,6 +223,8 @@ pub(crate) fn with_which(
         cwd,
         display_cwd: false,
         pipe_input: None,
+        null_stderr: false,
+        timeout: None,
     })
 }
 
@@ -42,6 +42,7 @@ where
                     name,
                     alias,
                     problem,
+                    validator,
                 },
             )| {
                 let (name, alias) = if let Some(alias) = alias {
@@ -53,7 +54,11 @@ where
                 };
                 (
                     name,
-                    PackageMetadataCargoCompeteBinExample { alias, problem },
+                    PackageMetadataCargoCompeteBinExample {
+                        alias,
+                        problem,
+                        validator,
+                    },
                 )
             },
         )
@@ -66,6 +71,7 @@ where
         alias: Option<String>,
         #[serde(deserialize_with = "deserialize_bin_problem")]
         problem: Url,
+        validator: Option<String>,
     }
 
     fn deserialize_bin_problem<'de, D>(deserializer: D) -> Result<Url, D::Error>
@@ -112,6 +118,8 @@ impl PackageMetadataCargoCompete {
 pub(crate) struct PackageMetadataCargoCompeteBinExample {
     pub(crate) alias: String,
     pub(crate) problem: Url,
+    /// Name or alias for a `bin`/`example` that validates the inputs of `problem`.
+    pub(crate) validator: Option<String>,
 }
 
 #[ext(MetadataExt)]
@@ -213,11 +221,25 @@ impl cm::Package {
         self.targets
             .iter()
             .find(|t| {
-                t.name == name && t.kind == ["bin".to_owned()] || t.kind == ["example".to_owned()]
+                t.name == name && (t.kind == ["bin".to_owned()] || t.kind == ["example".to_owned()])
             })
             .with_context(|| format!("no bin/example target named `{}` in `{}`", name, self.name))
     }
 
+    /// Looks up `package.metadata.cargo-compete` for `name_or_alias` first, since helper targets
+    /// such as generators are not necessarily registered there.
+    pub(crate) fn bin_like_target_by_name_or_alias(
+        &self,
+        package_metadata: &PackageMetadataCargoCompete,
+        name_or_alias: &str,
+    ) -> anyhow::Result<&cm::Target> {
+        let name = package_metadata
+            .bin_like_by_name_or_alias(name_or_alias)
+            .map(|(name, _)| name)
+            .unwrap_or(name_or_alias);
+        self.bin_like_target_by_name(name)
+    }
+
     pub(crate) fn bin_target_by_src_path(
         &self,
         src_path: impl AsRef<Path>,
@@ -334,12 +356,14 @@ mod tests {
                     problem: "https://atcoder.jp/contests/practice/tasks/practice_1"
                         .parse()
                         .unwrap(),
+                    validator: None,
                 },
                 "practice-b".to_owned() => PackageMetadataCargoCompeteBinExample {
                     alias: "b".to_owned(),
                     problem: "https://atcoder.jp/contests/practice/tasks/practice_2"
                         .parse()
                         .unwrap(),
+                    validator: Some("validate-b".to_owned()),
                 },
             ),
             example: indexmap!(),
@@ -350,7 +374,7 @@ mod tests {
             toml! {
                 [bin]
                 practice-a = { alias = "a", problem = "https://atcoder.jp/contests/practice/tasks/practice_1" }
-                practice-b = { alias = "b", problem = "https://atcoder.jp/contests/practice/tasks/practice_2" }
+                practice-b = { alias = "b", problem = "https://atcoder.jp/contests/practice/tasks/practice_2", validator = "validate-b" }
             }
             .try_into::<PackageMetadataCargoCompete>()?,
         );
@@ -361,6 +385,7 @@ mod tests {
                 "aplusb".to_owned() => PackageMetadataCargoCompeteBinExample {
                     alias: "aplusb".to_owned(),
                     problem: "https://judge.yosupo.jp/problem/aplusb".parse().unwrap(),
+                    validator: None,
                 },
             ),
             example: indexmap!(),
@@ -382,6 +407,7 @@ mod tests {
                 "aplusb".to_owned() => PackageMetadataCargoCompeteBinExample {
                     alias: "aplusb".to_owned(),
                     problem: "https://judge.yosupo.jp/problem/aplusb".parse().unwrap(),
+                    validator: None,
                 },
             ),
         };
@@ -1,8 +1,12 @@
 use indicatif::ProgressDrawTarget;
+use prettytable::{
+    format::{FormatBuilder, LinePosition, LineSeparator},
+    Row, Table,
+};
 use snowchains_core::{color_spec, web::StatusCodeColor};
 use std::{
     fmt,
-    io::{self, BufRead, Write},
+    io::{self, BufRead, Read as _, Write},
 };
 use strum::{EnumString, EnumVariantNames};
 use termcolor::{BufferedStandardStream, Color, NoColor, WriteColor};
@@ -66,6 +70,66 @@ impl Shell {
         stderr.flush()
     }
 
+    /// Prints an error with its causes. `main` also uses this before exiting.
+    pub fn error(&mut self, err: &anyhow::Error) -> io::Result<()> {
+        if self.needs_clear {
+            self.err_erase_line();
+        }
+
+        let stderr = self.err();
+
+        stderr.set_color(color_spec!(Bold, Fg(Color::Red)))?;
+        write!(stderr, "error:")?;
+        stderr.reset()?;
+        writeln!(stderr, " {err}")?;
+
+        for cause in err.chain().skip(1) {
+            writeln!(stderr)?;
+            stderr.set_color(color_spec!(Bold, Fg(Color::Red)))?;
+            write!(stderr, "Caused by:")?;
+            stderr.reset()?;
+            writeln!(stderr, "\n  {cause}")?;
+        }
+
+        stderr.flush()
+    }
+
+    /// Prints `rows` to stderr as a table with box-drawing characters.
+    pub(crate) fn print_table(&mut self, rows: &[Row]) -> io::Result<()> {
+        let mut table = Table::new();
+        *table.get_format() = FormatBuilder::new()
+            .padding(1, 1)
+            .column_separator('│')
+            .borders('│')
+            .separator(LinePosition::Top, LineSeparator::new('─', '┬', '┌', '┐'))
+            .separator(LinePosition::Title, LineSeparator::new('─', '┼', '├', '┤'))
+            .separator(LinePosition::Intern, LineSeparator::new('─', '┼', '├', '┤'))
+            .separator(LinePosition::Bottom, LineSeparator::new('─', '┴', '└', '┘'))
+            .build();
+        table.extend(rows.iter().cloned());
+
+        if self.needs_clear {
+            self.err_erase_line();
+        }
+        write!(self.err(), "{table}")?;
+        self.err().flush()
+    }
+
+    /// Clears the terminal if stderr is a TTY.
+    pub(crate) fn clear_screen(&mut self) -> io::Result<()> {
+        if let ShellOut::Stream {
+            stderr,
+            stderr_tty: true,
+            ..
+        } = &mut self.output
+        {
+            stderr.write_all(b"\x1B[2J\x1B[H")?;
+            stderr.flush()?;
+            self.needs_clear = false;
+        }
+        Ok(())
+    }
+
     pub(crate) fn status(
         &mut self,
         status: impl fmt::Display,
@@ -123,6 +187,11 @@ impl Shell {
         self.input.read_reply()
     }
 
+    /// Reads the whole input.
+    pub(crate) fn read_to_string(&mut self) -> io::Result<String> {
+        self.input.read_to_string()
+    }
+
     pub(crate) fn read_password(&mut self, prompt: &str) -> io::Result<String> {
         if self.needs_clear {
             self.err_erase_line();
@@ -209,6 +278,15 @@ impl ShellIn {
         }
     }
 
+    fn read_to_string(&mut self) -> io::Result<String> {
+        let mut buf = "".to_owned();
+        match self {
+            Self::Tty | Self::PipedStdin => io::stdin().read_to_string(&mut buf)?,
+            Self::Reader(r) => r.read_to_string(&mut buf)?,
+        };
+        Ok(buf)
+    }
+
     fn read_password(&mut self) -> io::Result<String> {
         match self {
             Self::Tty => rpassword::read_password_from_tty(None),
@@ -0,0 +1,199 @@
+use crate::shell::Shell;
+use anyhow::Context as _;
+use camino::{Utf8Path, Utf8PathBuf};
+use cargo_metadata as cm;
+use prettytable::row;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+use similar::TextDiff;
+use std::time::SystemTime;
+use url::Url;
+
+/// A line of the submission history.
+#[derive(Serialize, Deserialize, Debug)]
+pub(crate) struct Record {
+    time: String,
+    package: String,
+    bin: String,
+    bin_alias: String,
+    problem: Url,
+    language_id: String,
+    submission_url: Url,
+    /// SHA-256 of the submitted code.
+    source_hash: String,
+    code: String,
+}
+
+impl Record {
+    pub(crate) fn new(
+        package: &cm::Package,
+        bin: &cm::Target,
+        bin_alias: &str,
+        problem: &Url,
+        language_id: &str,
+        submission_url: &Url,
+        code: &str,
+    ) -> Self {
+        Self {
+            time: humantime::format_rfc3339_seconds(SystemTime::now()).to_string(),
+            package: package.name.clone(),
+            bin: bin.name.clone(),
+            bin_alias: bin_alias.to_owned(),
+            problem: problem.clone(),
+            language_id: language_id.to_owned(),
+            submission_url: submission_url.clone(),
+            source_hash: Sha256::digest(code.as_ref())
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect(),
+            code: code.to_owned(),
+        }
+    }
+
+    fn matches(&self, bin: Option<&str>, contest: Option<&str>) -> bool {
+        bin.into_iter()
+            .all(|bin| bin == self.bin || bin == self.bin_alias)
+            && contest
+                .into_iter()
+                .all(|contest| contest_id(&self.problem).as_deref() == Some(contest))
+    }
+}
+
+/// Returns the path to the submission history, which is next to `compete.toml`.
+///
+/// Unlike the target directory, it survives `cargo clean`.
+pub(crate) fn history_path(cargo_compete_config_path: &Utf8Path) -> Utf8PathBuf {
+    cargo_compete_config_path.with_file_name("submission-history.jsonl")
+}
+
+pub(crate) fn append(history_path: &Utf8Path, record: &Record) -> anyhow::Result<()> {
+    crate::fs::append_jsonl(history_path, record)
+}
+
+/// Prints the submissions with their IDs, which are 1-based line numbers in the history.
+pub(crate) fn list(
+    history_path: &Utf8Path,
+    bin: Option<&str>,
+    contest: Option<&str>,
+    shell: &mut Shell,
+) -> anyhow::Result<()> {
+    let records = read(history_path)?;
+
+    let mut rows = vec![row![
+        "ID",
+        "Time",
+        "Bin",
+        "Problem",
+        "Language ID",
+        "Size",
+        "SHA-256",
+        "URL (detail)",
+    ]];
+    for (id, record) in ids(&records) {
+        if record.matches(bin, contest) {
+            rows.push(row![
+                id,
+                record.time,
+                record.bin_alias,
+                record.problem,
+                record.language_id,
+                record.code.len(),
+                &record.source_hash[..record.source_hash.len().min(8)],
+                record.submission_url,
+            ]);
+        }
+    }
+
+    if rows.len() == 1 {
+        shell.warn("no submissions found")?;
+        return Ok(());
+    }
+    shell.print_table(&rows)?;
+    Ok(())
+}
+
+/// Writes the code of the submission to the standard output.
+pub(crate) fn show(history_path: &Utf8Path, id: usize, shell: &mut Shell) -> anyhow::Result<()> {
+    let records = read(history_path)?;
+    let record = find(&records, id)?;
+
+    shell.out().write_all(record.code.as_ref())?;
+    shell.out().flush()?;
+    Ok(())
+}
+
+/// Writes the difference between the code of the submissions in the unified format.
+pub(crate) fn diff(
+    history_path: &Utf8Path,
+    id1: usize,
+    id2: usize,
+    shell: &mut Shell,
+) -> anyhow::Result<()> {
+    let records = read(history_path)?;
+    let (record1, record2) = (find(&records, id1)?, find(&records, id2)?);
+
+    if record1.source_hash == record2.source_hash {
+        shell.status("Identical", format!("#{id1} and #{id2}"))?;
+        return Ok(());
+    }
+
+    let diff = TextDiff::from_lines(&record1.code, &record2.code)
+        .unified_diff()
+        .header(
+            &format!("#{} ({}, {})", id1, record1.bin_alias, record1.time),
+            &format!("#{} ({}, {})", id2, record2.bin_alias, record2.time),
+        )
+        .to_string();
+
+    shell.out().write_all(diff.as_ref())?;
+    shell.out().flush()?;
+    Ok(())
+}
+
+fn read(history_path: &Utf8Path) -> anyhow::Result<Vec<Record>> {
+    crate::fs::read_jsonl(history_path)
+}
+
+fn ids(records: &[Record]) -> impl Iterator<Item = (usize, &Record)> {
+    records.iter().enumerate().map(|(i, r)| (i + 1, r))
+}
+
+fn find(records: &[Record], id: usize) -> anyhow::Result<&Record> {
+    ids(records)
+        .find(|&(i, _)| i == id)
+        .map(|(_, record)| record)
+        .with_context(|| format!("no such submission: #{id}. see `cargo compete history list`"))
+}
+
+/// Returns the contest ID in the problem URL, which follows `contests` or `contest`.
+fn contest_id(problem: &Url) -> Option<String> {
+    let mut segments = problem.path_segments()?;
+    segments.find(|s| ["contests", "contest"].contains(s))?;
+    segments
+        .next()
+        .filter(|s| !s.is_empty())
+        .map(ToOwned::to_owned)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::submission_history::contest_id;
+    use pretty_assertions::assert_eq;
+    use url::Url;
+
+    #[test]
+    fn contest_id_in_problem_url() -> anyhow::Result<()> {
+        let contest_id = |url: &str| -> anyhow::Result<_> { Ok(contest_id(&url.parse::<Url>()?)) };
+
+        assert_eq!(
+            Some("abc123".to_owned()),
+            contest_id("https://atcoder.jp/contests/abc123/tasks/abc123_a")?,
+        );
+        assert_eq!(
+            Some("1234".to_owned()),
+            contest_id("https://codeforces.com/contest/1234/problem/A")?,
+        );
+        assert_eq!(None, contest_id("https://yukicoder.me/problems/no/1")?);
+        Ok(())
+    }
+}
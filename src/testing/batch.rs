@@ -0,0 +1,645 @@
+use super::validator::Validator;
+use anyhow::{anyhow, Context as _};
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use itertools::{EitherOrBoth, Itertools as _};
+use snowchains_core::{
+    color_spec,
+    judge::{CommandExpression, WrongAnswerNote},
+    testsuite::{BatchTestCase, CheckerShell, DeterministicExpectedOutput, ExpectedOutput},
+};
+use std::{
+    env,
+    ffi::OsStr,
+    fs, io,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    process::{ExitStatus, Output, Stdio},
+    sync::{
+        atomic::{self, AtomicUsize},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+use termcolor::{Color, WriteColor};
+use unicode_width::UnicodeWidthStr as _;
+
+#[derive(Debug)]
+pub(crate) struct JudgeOutcome {
+    /// In the order of the test cases. Ones skipped by `fail_fast` are missing.
+    pub(crate) verdicts: Vec<Verdict>,
+    /// Number of the test cases skipped by `fail_fast`.
+    pub(crate) num_skipped: usize,
+}
+
+impl JudgeOutcome {
+    pub(crate) fn print_pretty(
+        &self,
+        mut wtr: impl WriteColor,
+        display_limit: Option<usize>,
+        diff: bool,
+    ) -> io::Result<()> {
+        for (i, verdict) in self.verdicts.iter().enumerate() {
+            if i > 0 {
+                writeln!(wtr)?;
+            }
+
+            write!(
+                wtr,
+                "{}/{} ({:?}) ",
+                verdict.index + 1,
+                self.verdicts.len() + self.num_skipped,
+                verdict.test_case_name.as_deref().unwrap_or(""),
+            )?;
+
+            wtr.set_color(color_spec!(Bold, Fg(verdict.kind.summary_color())))?;
+            writeln!(wtr, "{}", verdict.summary())?;
+            wtr.reset()?;
+
+            let is_float = matches!(
+                verdict.expected,
+                ExpectedOutput::Deterministic(DeterministicExpectedOutput::Float { .. }),
+            );
+
+            write_text(&mut wtr, "stdin:", &verdict.stdin, false, display_limit)?;
+
+            let diffable = match (&verdict.expected, &verdict.stdout) {
+                (ExpectedOutput::Deterministic(expected), Some(stdout))
+                    if diff
+                        && verdict.kind == VerdictKind::WrongAnswer
+                        && expected_stdout(expected).is_some() =>
+                {
+                    Some((expected, stdout))
+                }
+                _ => None,
+            };
+
+            if let Some((expected, stdout)) = diffable {
+                super::diff::write_diff(&mut wtr, expected, stdout)?;
+            }
+
+            let mut write_text = |header: &str, text: &str, highlight_numbers: bool| {
+                write_text(&mut wtr, header, text, highlight_numbers, display_limit)
+            };
+
+            if diffable.is_none() {
+                match &verdict.expected {
+                    ExpectedOutput::Deterministic(expected) => {
+                        if let Some(expected) = expected_stdout(expected) {
+                            write_text("expected:", expected, is_float)?;
+                        }
+                    }
+                    ExpectedOutput::Checker { text, .. } => {
+                        if let Some(example) = text {
+                            write_text("example:", example, false)?;
+                        }
+                    }
+                }
+                if let Some(stdout) = &verdict.stdout {
+                    write_text("actual:", stdout, is_float)?;
+                }
+            }
+            if let Some(stderr) = verdict.stderr.as_deref().filter(|s| !s.is_empty()) {
+                write_text("stderr:", stderr, is_float)?;
+            }
+            if !verdict.checker_stdout.is_empty() {
+                write_text("checker stdout: ", &verdict.checker_stdout, false)?;
+            }
+            if !verdict.checker_stderr.is_empty() {
+                write_text("checker stderr: ", &verdict.checker_stderr, false)?;
+            }
+            if !verdict.validator_stderr.is_empty() {
+                write_text("validator stderr: ", &verdict.validator_stderr, false)?;
+            }
+            if let Some(note) = verdict.note {
+                write_text("note: ", &format!("{note}\n"), false)?;
+            }
+        }
+
+        wtr.flush()
+    }
+}
+
+fn write_text(
+    mut wtr: impl WriteColor,
+    header: &str,
+    text: &str,
+    highlight_numbers: bool,
+    display_limit: Option<usize>,
+) -> io::Result<()> {
+    wtr.set_color(color_spec!(Bold, Fg(Color::Magenta)))?;
+    writeln!(wtr, "{header}")?;
+    wtr.reset()?;
+
+    if text.is_empty() {
+        wtr.set_color(color_spec!(Bold, Fg(Color::Yellow)))?;
+        writeln!(wtr, "EMPTY")?;
+        return wtr.reset();
+    }
+
+    if matches!(display_limit, Some(l) if l < text.len()) {
+        wtr.set_color(color_spec!(Bold, Fg(Color::Yellow)))?;
+        writeln!(wtr, "{} B", text.len())?;
+        return wtr.reset();
+    }
+
+    let is_plain = |c: char| !(c.is_whitespace() || c.is_control());
+
+    for (plain, token) in text
+        .chars()
+        .group_by(|&c| is_plain(c))
+        .into_iter()
+        .map(|(plain, token)| (plain, token.collect::<String>()))
+    {
+        if plain {
+            if highlight_numbers && token.parse::<f64>().is_ok() {
+                wtr.set_color(color_spec!(Fg(Color::Cyan)))?;
+                wtr.write_all(token.as_ref())?;
+                wtr.reset()?;
+            } else {
+                wtr.write_all(token.as_ref())?;
+            }
+            continue;
+        }
+
+        for c in token.chars() {
+            match c {
+                ' ' | '\n' => write!(wtr, "{c}")?,
+                '\r' | '\t' => {
+                    wtr.set_color(color_spec!(Fg(Color::Yellow)))?;
+                    write!(wtr, "{}", c.escape_default())?;
+                    wtr.reset()?;
+                }
+                c => {
+                    wtr.set_color(color_spec!(Fg(Color::Yellow)))?;
+                    write!(wtr, "{}", c.escape_unicode())?;
+                    wtr.reset()?;
+                }
+            }
+        }
+    }
+
+    if !text.ends_with('\n') {
+        wtr.set_color(color_spec!(Fg(Color::Yellow)))?;
+        writeln!(wtr, "⏎")?;
+        wtr.reset()?;
+    }
+    Ok(())
+}
+
+#[derive(Debug)]
+pub(crate) struct Verdict {
+    /// 0-based index in the test cases.
+    pub(crate) index: usize,
+    pub(crate) test_case_name: Option<String>,
+    pub(crate) kind: VerdictKind,
+    pub(crate) elapsed: Duration,
+    /// Peak resident set size in bytes. Only available on Linux.
+    pub(crate) peak_memory: Option<u64>,
+    pub(crate) stdin: Arc<str>,
+    /// `None` if the program was killed.
+    pub(crate) stdout: Option<Arc<str>>,
+    /// `None` if the program was killed.
+    pub(crate) stderr: Option<Arc<str>>,
+    pub(crate) expected: ExpectedOutput,
+    pub(crate) checker_stdout: Arc<str>,
+    pub(crate) checker_stderr: Arc<str>,
+    pub(crate) validator_stderr: Arc<str>,
+    pub(crate) note: Option<WrongAnswerNote>,
+}
+
+impl Verdict {
+    pub(crate) fn summary(&self) -> String {
+        let usage = match self.peak_memory {
+            Some(peak_memory) => format!(
+                "{} ms, {}",
+                self.elapsed.as_millis(),
+                display_memory(peak_memory),
+            ),
+            None => format!("{} ms", self.elapsed.as_millis()),
+        };
+        match &self.kind {
+            VerdictKind::Accepted => format!("Accepted ({usage})"),
+            VerdictKind::WrongAnswer => format!("Wrong Answer ({usage})"),
+            VerdictKind::RuntimeError(status) => format!("Runtime Error ({usage}, {status})"),
+            VerdictKind::StackOverflow(_) => format!("Runtime Error ({usage}, stack overflow)"),
+            VerdictKind::TimelimitExceeded(timelimit) => {
+                format!("Timelimit Exceeded ({} ms)", timelimit.as_millis())
+            }
+            VerdictKind::MemoryLimitExceeded(memory_limit) => {
+                format!("Memory Limit Exceeded ({})", display_memory(*memory_limit))
+            }
+            VerdictKind::InvalidInput => "Invalid Input".to_owned(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum VerdictKind {
+    Accepted,
+    WrongAnswer,
+    RuntimeError(ExitStatus),
+    /// A runtime error caused by a stack overflow.
+    StackOverflow(ExitStatus),
+    TimelimitExceeded(Duration),
+    /// The limit in bytes.
+    MemoryLimitExceeded(u64),
+    /// The input was rejected by the validator. The program is not run.
+    InvalidInput,
+}
+
+impl VerdictKind {
+    fn summary_color(&self) -> Color {
+        match self {
+            Self::Accepted => Color::Green,
+            Self::TimelimitExceeded(_) | Self::MemoryLimitExceeded(_) => Color::Red,
+            Self::WrongAnswer | Self::RuntimeError(_) | Self::StackOverflow(_) => Color::Yellow,
+            Self::InvalidInput => Color::Magenta,
+        }
+    }
+
+    fn summary_style(&self) -> &'static str {
+        match self {
+            Self::Accepted => ".bold.green",
+            Self::TimelimitExceeded(_) | Self::MemoryLimitExceeded(_) => ".bold.red",
+            Self::WrongAnswer | Self::RuntimeError(_) | Self::StackOverflow(_) => ".bold.yellow",
+            Self::InvalidInput => ".bold.magenta",
+        }
+    }
+}
+
+pub(super) fn display_memory(bytes: u64) -> String {
+    if bytes < 1024 * 1024 {
+        format!("{} KiB", bytes / 1024)
+    } else {
+        format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
+
+/// The default number of the jobs.
+///
+/// The test cases are run one by one by default, since parallel runs make the elapsed time
+/// unstable.
+pub(crate) fn default_jobs() -> NonZeroUsize {
+    NonZeroUsize::new(1).unwrap()
+}
+
+/// Runs `cmd` for each test case with at most `jobs` processes at a time.
+///
+/// The verdicts are in the same order as `test_cases`, regardless of which finishes first.
+/// `memory_limit` is in bytes and is only enforced where the peak memory can be measured.
+/// If `validator` is given, it checks each input before `cmd` runs.
+///
+/// With `fail_fast`, the test cases after the first failure are skipped unless they have already
+/// started. The verdicts of the ones that have finished are all kept.
+///
+/// On Ctrl-C, the running programs are killed and this returns `Err`.
+pub(crate) fn judge(
+    draw_target: ProgressDrawTarget,
+    cmd: &CommandExpression,
+    test_cases: &[BatchTestCase],
+    memory_limit: Option<u64>,
+    validator: Option<&Validator>,
+    jobs: NonZeroUsize,
+    fail_fast: bool,
+) -> anyhow::Result<JudgeOutcome> {
+    let num_test_cases = test_cases.len();
+
+    let quoted_name_width = test_cases
+        .iter()
+        .flat_map(|BatchTestCase { name, .. }| name.as_ref())
+        .map(|s| format!("{s:?}").width())
+        .max()
+        .unwrap_or(0);
+
+    // The same as `snowchains_core`. Bash is only required for checkers.
+    let bash_exe = {
+        static GIT_BASH: &str = r"C:\Program Files\Git\bin\bash.exe";
+
+        let bash_exe = if cfg!(windows) && Path::new(GIT_BASH).exists() {
+            GIT_BASH
+        } else {
+            "bash"
+        };
+        which::which_in(bash_exe, env::var_os("PATH"), &cmd.cwd)
+            .map_err(|_| anyhow!("`{}` not found", bash_exe))
+    };
+
+    super::listen_ctrl_c()?;
+
+    let tempdir = tempfile::Builder::new()
+        .prefix("cargo-compete-judging-")
+        .tempdir()?;
+
+    let mp = MultiProgress::with_draw_target(draw_target);
+
+    let pbs = test_cases
+        .iter()
+        .enumerate()
+        .map(|(i, test_case)| {
+            let pb = mp.add(ProgressBar::new_spinner());
+            pb.set_style(progress_style("{prefix}{spinner} {msg:bold}"));
+            pb.set_prefix(&format!(
+                "{}/{} ({} ",
+                align_right(&(i + 1).to_string(), num_test_cases.to_string().len()),
+                num_test_cases,
+                align_left(
+                    &format!("{:?})", test_case.name.as_deref().unwrap_or("")),
+                    quoted_name_width + 1,
+                ),
+            ));
+            pb.set_message("Judging...");
+            pb.enable_steady_tick(50);
+            pb
+        })
+        .collect::<Vec<_>>();
+
+    let next = AtomicUsize::new(0);
+    let first_failure = AtomicUsize::new(usize::MAX);
+    let results = Mutex::new((0..num_test_cases).map(|_| None).collect::<Vec<_>>());
+
+    thread::scope(|scope| -> anyhow::Result<()> {
+        for _ in 0..jobs.get().min(num_test_cases) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, atomic::Ordering::SeqCst);
+                if i >= num_test_cases || super::check_ctrl_c().is_err() {
+                    break;
+                }
+
+                if i > first_failure.load(atomic::Ordering::SeqCst) {
+                    pbs[i].set_style(progress_style("{prefix}{msg}"));
+                    pbs[i].finish_with_message("Skipped");
+                    continue;
+                }
+
+                let result = judge_one(
+                    cmd,
+                    &test_cases[i],
+                    memory_limit,
+                    validator,
+                    i,
+                    tempdir.path(),
+                    &bash_exe,
+                );
+
+                match &result {
+                    Ok(verdict) => {
+                        pbs[i].set_style(progress_style(format!(
+                            "{{prefix}}{{msg:{}}}",
+                            verdict.kind.summary_style(),
+                        )));
+                        pbs[i].finish_with_message(&verdict.summary());
+                    }
+                    Err(err) => {
+                        pbs[i].set_style(progress_style("{prefix}{msg}"));
+                        pbs[i].finish_with_message(&format!("{err:?}"));
+                    }
+                }
+
+                if fail_fast && !matches!(&result, Ok(v) if v.kind == VerdictKind::Accepted) {
+                    first_failure.fetch_min(i, atomic::Ordering::SeqCst);
+                }
+
+                results.lock().unwrap()[i] = Some(result);
+            });
+        }
+        mp.join()?;
+        Ok(())
+    })?;
+
+    super::check_ctrl_c()?;
+
+    // With `fail_fast`, the test cases after a skipped one may have finished.
+    let verdicts = results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let num_skipped = num_test_cases - verdicts.len();
+
+    tempdir.close()?;
+    return Ok(JudgeOutcome {
+        verdicts,
+        num_skipped,
+    });
+
+    fn progress_style(template: impl AsRef<str>) -> ProgressStyle {
+        ProgressStyle::default_spinner().template(template.as_ref())
+    }
+
+    fn align_left(s: &str, n: usize) -> String {
+        let spaces = n.saturating_sub(s.width());
+        s.chars().chain(itertools::repeat_n(' ', spaces)).collect()
+    }
+
+    fn align_right(s: &str, n: usize) -> String {
+        let spaces = n.saturating_sub(s.width());
+        itertools::repeat_n(' ', spaces).chain(s.chars()).collect()
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn judge_one(
+    cmd: &CommandExpression,
+    test_case: &BatchTestCase,
+    memory_limit: Option<u64>,
+    validator: Option<&Validator>,
+    i: usize,
+    tempdir: &Path,
+    bash_exe: &anyhow::Result<PathBuf>,
+) -> anyhow::Result<Verdict> {
+    let stdin_path = tempdir.join(format!("{i}-stdin"));
+    let actual_stdout_path = tempdir.join(format!("{i}-actual-stdout"));
+    let expected_stdout_path = tempdir.join(format!("{i}-expected-stdout"));
+    let stderr_path = tempdir.join(format!("{i}-stderr"));
+
+    crate::fs::write(&stdin_path, &*test_case.input)?;
+
+    let mut verdict = Verdict {
+        index: i,
+        test_case_name: test_case.name.clone(),
+        kind: VerdictKind::Accepted,
+        elapsed: Duration::ZERO,
+        peak_memory: None,
+        stdin: test_case.input.clone(),
+        stdout: None,
+        stderr: None,
+        expected: test_case.output.clone(),
+        checker_stdout: "".into(),
+        checker_stderr: "".into(),
+        validator_stderr: "".into(),
+        note: None,
+    };
+
+    if let Some(validator) = validator {
+        if let Some(stderr) = validator.validate(&test_case.input)? {
+            verdict.kind = VerdictKind::InvalidInput;
+            verdict.validator_stderr = stderr.into();
+            return Ok(verdict);
+        }
+    }
+
+    let started = Instant::now();
+
+    let mut child = std::process::Command::new(&cmd.program)
+        .args(&cmd.args)
+        .current_dir(&cmd.cwd)
+        .envs(&cmd.env)
+        .stdin(fs::File::open(&stdin_path)?)
+        .stdout(fs::File::create(&actual_stdout_path)?)
+        .stderr(fs::File::create(&stderr_path)?)
+        .spawn()
+        .with_context(|| format!("could not execute `{}`", cmd.program.to_string_lossy()))?;
+
+    let deadline = test_case
+        .timelimit
+        .map(|t| started + t + Duration::from_millis(100));
+
+    let exited = super::wait_with_deadline(&mut child, deadline)?;
+    let elapsed = started.elapsed();
+
+    verdict.elapsed = elapsed;
+
+    let super::Exited {
+        status,
+        peak_memory,
+    } = match (exited, test_case.timelimit) {
+        (Some(_), Some(timelimit)) if timelimit < elapsed => {
+            verdict.kind = VerdictKind::TimelimitExceeded(timelimit);
+            return Ok(verdict);
+        }
+        (Some(exited), _) => exited,
+        (None, timelimit) => {
+            let _ = child.kill();
+            let _ = child.wait();
+            verdict.kind = VerdictKind::TimelimitExceeded(timelimit.unwrap());
+            return Ok(verdict);
+        }
+    };
+
+    verdict.peak_memory = peak_memory;
+
+    if let (Some(peak_memory), Some(memory_limit)) = (peak_memory, memory_limit) {
+        if peak_memory > memory_limit {
+            verdict.kind = VerdictKind::MemoryLimitExceeded(memory_limit);
+            return Ok(verdict);
+        }
+    }
+
+    let stdout = utf8(crate::fs::read(&actual_stdout_path)?)?;
+    verdict.stdout = Some(stdout.clone());
+    let stderr = utf8(crate::fs::read(&stderr_path)?)?;
+    verdict.stderr = Some(stderr.clone());
+
+    if !status.success() {
+        verdict.kind = if super::is_stack_overflow(&stderr) {
+            VerdictKind::StackOverflow(status)
+        } else {
+            VerdictKind::RuntimeError(status)
+        };
+        return Ok(verdict);
+    }
+
+    match &test_case.output {
+        ExpectedOutput::Deterministic(expected) => {
+            if !accepts(expected, &stdout) {
+                verdict.kind = VerdictKind::WrongAnswer;
+                verdict.note = expected_stdout(expected)
+                    .filter(|expected| expected.split_whitespace().eq(stdout.split_whitespace()))
+                    .map(|_| WrongAnswerNote::WordsMatched);
+            }
+        }
+        ExpectedOutput::Checker {
+            text,
+            cmd: checker,
+            shell,
+        } => {
+            let (program, args) = match shell {
+                CheckerShell::Bash => (
+                    bash_exe.as_ref().map_err(|e| anyhow!("{}", e))?,
+                    [OsStr::new("-c"), OsStr::new(checker)],
+                ),
+            };
+
+            let mut env_vars = vec![
+                ("INPUT", &*stdin_path),
+                ("ACTUAL_OUTPUT", &*actual_stdout_path),
+            ];
+            if let Some(text) = text {
+                crate::fs::write(&expected_stdout_path, &**text)?;
+                env_vars.push(("EXPECTED_OUTPUT", &*expected_stdout_path));
+            }
+
+            let Output {
+                status,
+                stdout,
+                stderr,
+            } = std::process::Command::new(program)
+                .args(args)
+                .envs(env_vars)
+                .current_dir(&cmd.cwd)
+                .stdin(Stdio::null())
+                .output()
+                .with_context(|| format!("could not execute `{}`", program.display()))?;
+
+            if !status.success() {
+                verdict.kind = VerdictKind::WrongAnswer;
+                verdict.checker_stdout = utf8(stdout)?;
+                verdict.checker_stderr = utf8(stderr)?;
+            }
+        }
+    }
+
+    Ok(verdict)
+}
+
+/// The same as `DeterministicExpectedOutput::accepts` in `snowchains_core`, which is private.
+fn accepts(expected: &DeterministicExpectedOutput, actual: &str) -> bool {
+    match expected {
+        DeterministicExpectedOutput::Pass => true,
+        DeterministicExpectedOutput::Exact { text } => &**text == actual,
+        DeterministicExpectedOutput::SplitWhitespace { text } => {
+            text.split_whitespace().eq(actual.split_whitespace())
+        }
+        DeterministicExpectedOutput::Lines { text } => text.lines().eq(actual.lines()),
+        DeterministicExpectedOutput::Float {
+            text,
+            relative_error,
+            absolute_error,
+        } => {
+            let relative_error = relative_error.map(|e| e.get()).unwrap_or(0.0);
+            let absolute_error = absolute_error.map(|e| e.get()).unwrap_or(0.0);
+
+            text.lines().zip_longest(actual.lines()).all(|zip| {
+                if let EitherOrBoth::Both(line1, line2) = zip {
+                    let (words1, words2) = (line1.split_whitespace(), line2.split_whitespace());
+                    words1.zip_longest(words2).all(|zip| match zip {
+                        EitherOrBoth::Both(s1, s2) => {
+                            super::diff::float_words_match(s1, s2, relative_error, absolute_error)
+                        }
+                        EitherOrBoth::Left(_) | EitherOrBoth::Right(_) => false,
+                    })
+                } else {
+                    false
+                }
+            })
+        }
+    }
+}
+
+pub(crate) fn expected_stdout(expected: &DeterministicExpectedOutput) -> Option<&str> {
+    match expected {
+        DeterministicExpectedOutput::Pass => None,
+        DeterministicExpectedOutput::Exact { text }
+        | DeterministicExpectedOutput::SplitWhitespace { text }
+        | DeterministicExpectedOutput::Lines { text }
+        | DeterministicExpectedOutput::Float { text, .. } => Some(text),
+    }
+}
+
+fn utf8(bytes: Vec<u8>) -> anyhow::Result<Arc<str>> {
+    String::from_utf8(bytes)
+        .map(Into::into)
+        .map_err(|_| anyhow!("the output was not a valid UTF-8 string"))
+}
@@ -0,0 +1,119 @@
+use super::batch::{self, JudgeOutcome, VerdictKind};
+use crate::shell::Shell;
+use indicatif::ProgressDrawTarget;
+use prettytable::{row, Row};
+use snowchains_core::{judge::CommandExpression, testsuite::BatchTestCase};
+use std::{num::NonZeroUsize, time::Duration};
+
+/// Runs the test cases `repeat - 1` more times and prints the statistics of the elapsed time
+/// including `first`.
+///
+/// The inputs have already been validated in `first`. The ones rejected or skipped there are
+/// excluded, and the rest are not validated again.
+///
+/// Test cases whose median exceeds `tle_risk` of the timelimit are warned.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn bench(
+    cmd: &CommandExpression,
+    test_cases: &[BatchTestCase],
+    first: &JudgeOutcome,
+    memory_limit: Option<u64>,
+    jobs: NonZeroUsize,
+    repeat: NonZeroUsize,
+    tle_risk: f64,
+    shell: &mut Shell,
+) -> anyhow::Result<()> {
+    let (test_cases, mut elapsed): (Vec<_>, Vec<_>) = first
+        .verdicts
+        .iter()
+        .filter(|v| v.kind != VerdictKind::InvalidInput)
+        .map(|v| (test_cases[v.index].clone(), vec![v.elapsed]))
+        .unzip();
+
+    if test_cases.is_empty() {
+        return Ok(());
+    }
+
+    for i in 1..repeat.get() {
+        shell.status("Benchmarking", format!("{}/{}", i + 1, repeat))?;
+        let outcome = batch::judge(
+            ProgressDrawTarget::hidden(),
+            cmd,
+            &test_cases,
+            memory_limit,
+            None,
+            jobs,
+            false,
+        )?;
+        for (acc, verdict) in elapsed.iter_mut().zip(&outcome.verdicts) {
+            acc.push(verdict.elapsed);
+        }
+    }
+
+    let mut rows = vec![row!["Test case", "Min", "Median", "Max", "Timelimit", ""]];
+    let mut risks = vec![];
+
+    for (test_case, mut elapsed) in test_cases.iter().zip(elapsed) {
+        elapsed.sort();
+        let name = format!("{:?}", test_case.name.as_deref().unwrap_or(""));
+        let (min, median, max) = (elapsed[0], median(&elapsed), elapsed[elapsed.len() - 1]);
+
+        let is_risky = matches!(
+            test_case.timelimit,
+            Some(timelimit) if median.as_secs_f64() > timelimit.as_secs_f64() * tle_risk
+        );
+        if is_risky {
+            risks.push((name.clone(), median, test_case.timelimit.unwrap()));
+        }
+
+        rows.push(Row::from(vec![
+            name,
+            display_ms(min),
+            display_ms(median),
+            display_ms(max),
+            test_case.timelimit.map(display_ms).unwrap_or_default(),
+            if is_risky { "TLE risk" } else { "" }.to_owned(),
+        ]));
+    }
+
+    shell.print_table(&rows)?;
+
+    for (name, median, timelimit) in risks {
+        shell.warn(format!(
+            "{}: the median ({}) is {:.0}% of the timelimit ({})",
+            name,
+            display_ms(median),
+            median.as_secs_f64() / timelimit.as_secs_f64() * 100.0,
+            display_ms(timelimit),
+        ))?;
+    }
+    Ok(())
+}
+
+/// Returns the median of sorted `elapsed`.
+fn median(elapsed: &[Duration]) -> Duration {
+    let n = elapsed.len();
+    if n % 2 == 1 {
+        elapsed[n / 2]
+    } else {
+        (elapsed[n / 2 - 1] + elapsed[n / 2]) / 2
+    }
+}
+
+fn display_ms(elapsed: Duration) -> String {
+    format!("{} ms", elapsed.as_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::bench::median;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    #[test]
+    fn median_of_odd_and_even() {
+        let ms = Duration::from_millis;
+        assert_eq!(ms(2), median(&[ms(1), ms(2), ms(9)]));
+        assert_eq!(ms(3), median(&[ms(1), ms(2), ms(4), ms(9)]));
+    }
+}
@@ -0,0 +1,351 @@
+use super::suite_yaml::{self, skip_back_blank_lines, to_yaml_value, Offsets};
+use crate::{project::PackageExt as _, shell::Shell};
+use anyhow::{bail, ensure, Context as _};
+use camino::{Utf8Path, Utf8PathBuf};
+use cargo_metadata as cm;
+use ignore::{overrides::OverrideBuilder, WalkBuilder};
+use snowchains_core::testsuite::{Additional, BatchTestSuite, TestSuite};
+use std::{ops::Range, path::Path};
+use url::Url;
+use yaml_rust::scanner::Marker;
+
+pub(crate) struct Args<'a> {
+    pub(crate) metadata: &'a cm::Metadata,
+    pub(crate) member: &'a cm::Package,
+    pub(crate) bin: &'a cm::Target,
+    pub(crate) bin_alias: &'a str,
+    pub(crate) reference: &'a cm::Target,
+    pub(crate) cargo_compete_config_test_suite: &'a liquid::Template,
+    pub(crate) problem_url: &'a Url,
+    pub(crate) toolchain: Option<&'a str>,
+    pub(crate) release: bool,
+    pub(crate) force: bool,
+    pub(crate) shell: &'a mut Shell,
+}
+
+/// Writes the outputs of `reference` as the expected outputs of the test cases without them, or
+/// of all of the test cases if `force` is `true`.
+///
+/// The test suite file is edited in place so that the other fields, comments, and formatting are
+/// kept. Text files in `extend` get `out` files next to them.
+pub(crate) fn bless(args: Args<'_>) -> anyhow::Result<()> {
+    let Args {
+        metadata,
+        member,
+        bin,
+        bin_alias,
+        reference,
+        cargo_compete_config_test_suite,
+        problem_url,
+        toolchain,
+        release,
+        force,
+        shell,
+    } = args;
+
+    let test_suite_path = super::test_suite_path(
+        &metadata.workspace_root,
+        member.manifest_dir(),
+        cargo_compete_config_test_suite,
+        &bin.name,
+        bin_alias,
+        problem_url,
+        shell,
+    )?;
+
+    let test_suite_dir = test_suite_path.parent().unwrap();
+
+    let yaml = crate::fs::read_to_string(&test_suite_path)?;
+
+    let test_suite = match crate::fs::read_yaml(&test_suite_path)? {
+        TestSuite::Batch(test_suite) => test_suite,
+        TestSuite::Interactive(_) => bail!("`--bless` does not support `Interactive` problems"),
+        TestSuite::Unsubmittable => bail!("this is `Unsubmittable` problem"),
+    };
+
+    let text_files = text_files_to_bless(&test_suite, test_suite_dir, force)?;
+
+    let targets = test_suite
+        .cases
+        .iter()
+        .enumerate()
+        .filter(|(_, case)| force || case.out.is_none())
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+
+    if targets.is_empty() && text_files.is_empty() {
+        shell.status("Finished", "all of the test cases have expected outputs")?;
+        return Ok(());
+    }
+
+    let reference = &super::build(metadata, member, reference, toolchain, release, shell)?;
+
+    let run_reference = |name: &str, input: &str| -> anyhow::Result<String> {
+        crate::process::process(reference)
+            .pipe_input(Some(input))
+            .cwd(&metadata.workspace_root)
+            .read()
+            .with_context(|| format!("the reference solution failed on `{name}`"))
+    };
+
+    let mut outputs = vec![];
+    for &i in &targets {
+        let case = &test_suite.cases[i];
+        let name = case.name.clone().unwrap_or_else(|| format!("cases[{i}]"));
+        outputs.push((i, run_reference(&name, &case.r#in)?));
+    }
+
+    if !outputs.is_empty() {
+        let edited = set_outputs(&yaml, &outputs).with_context(|| {
+            format!(
+                "could not edit `{test_suite_path}`. write `out` by hand, or move the case to \
+                 text files",
+            )
+        })?;
+        crate::fs::write(&test_suite_path, edited)?;
+    }
+
+    for (name, in_path, out_path) in &text_files {
+        let output = run_reference(name, &crate::fs::read_to_string(in_path)?)?;
+        crate::fs::create_dir_all(out_path.parent().unwrap())?;
+        crate::fs::write(out_path, output)?;
+    }
+
+    let n = outputs.len() + text_files.len();
+    shell.status(
+        "Blessed",
+        format!(
+            "{} test case{} with {}",
+            n,
+            if n == 1 { "" } else { "s" },
+            reference.file_name().unwrap_or_default(),
+        ),
+    )?;
+    Ok(())
+}
+
+/// Returns the input files in `extend` that do not have output files yet, with the paths of the
+/// output files.
+fn text_files_to_bless(
+    test_suite: &BatchTestSuite,
+    test_suite_dir: &Utf8Path,
+    force: bool,
+) -> anyhow::Result<Vec<(String, Utf8PathBuf, Utf8PathBuf)>> {
+    let mut acc = vec![];
+
+    for extend in &test_suite.extend {
+        let (base, r#in, out) = match extend {
+            Additional::Text {
+                path, r#in, out, ..
+            } => (path, r#in, out),
+            Additional::SystemTestCases { .. } => continue,
+        };
+
+        // The same as `snowchains_core`.
+        let base = Path::new(base);
+        let base = test_suite_dir
+            .as_std_path()
+            .join(base.strip_prefix(".").unwrap_or(base));
+
+        ensure!(
+            out.matches('*').count() == 1 && !out.contains(&['?', '[', '{'][..]),
+            "cannot determine paths of output files from `out: {:?}`. it should contain exactly \
+             one `*`",
+            out,
+        );
+
+        for entry in WalkBuilder::new(&base)
+            .max_depth(Some(128))
+            .overrides(OverrideBuilder::new(&base).add(r#in)?.build()?)
+            .standard_filters(false)
+            .build()
+        {
+            let in_path = entry?.into_path();
+            if in_path.is_dir() {
+                continue;
+            }
+
+            let in_path = Utf8PathBuf::from_path_buf(in_path)
+                .map_err(|p| anyhow::anyhow!("non UTF-8 path: {}", p.display()))?;
+            let name = in_path.file_stem().unwrap_or_default().to_owned();
+
+            let out_path = Utf8PathBuf::from_path_buf(
+                base.join(out.trim_start_matches('/').replace('*', &name)),
+            )
+            .map_err(|p| anyhow::anyhow!("non UTF-8 path: {}", p.display()))?;
+
+            if force || !out_path.exists() {
+                acc.push((name, in_path, out_path));
+            }
+        }
+    }
+
+    Ok(acc)
+}
+
+/// Sets `out` of `cases[i]` for each `(i, output)`, editing `yaml` as text.
+///
+/// The result is checked by parsing it again.
+fn set_outputs(yaml: &str, outputs: &[(usize, String)]) -> anyhow::Result<String> {
+    let spans = suite_yaml::cases_span(yaml)?.cases;
+    let offsets = Offsets::new(yaml);
+    let offset = |marker: &Marker| offsets.of(marker);
+    let line_start = |marker: &Marker| offsets.line_start(marker);
+
+    let mut edits = vec![];
+
+    for (i, output) in outputs {
+        let span = spans
+            .get(*i)
+            .with_context(|| format!("`cases[{i}]` not found"))?;
+        let indent = span.in_key.col();
+
+        let edit = match &span.out {
+            Some((key, value_end)) => {
+                let end = skip_back_blank_lines(yaml, line_start(value_end));
+                ensure!(offset(key) < end, "`cases[{}]` is not in block style", i,);
+                (
+                    offset(key)..end,
+                    format!("out: {}", to_yaml_value(output, indent)),
+                )
+            }
+            None => {
+                let pos = skip_back_blank_lines(yaml, line_start(&span.end));
+                ensure!(
+                    offset(&span.in_key) < pos,
+                    "`cases[{}]` is not in block style",
+                    i,
+                );
+                let prefix = if yaml[..pos].ends_with('\n') {
+                    ""
+                } else {
+                    "\n"
+                };
+                (
+                    pos..pos,
+                    format!(
+                        "{}{:indent$}out: {}",
+                        prefix,
+                        "",
+                        to_yaml_value(output, indent),
+                        indent = indent,
+                    ),
+                )
+            }
+        };
+        edits.push(edit);
+    }
+
+    edits.sort_by_key(|(range, _)| range.start);
+
+    let mut edited = yaml.to_owned();
+    for (Range { start, end }, text) in edits.into_iter().rev() {
+        edited.replace_range(start..end, &text);
+    }
+
+    let (original, edited_test_suite) = match (
+        serde_yaml::from_str::<TestSuite>(yaml)?,
+        serde_yaml::from_str::<TestSuite>(&edited),
+    ) {
+        (TestSuite::Batch(original), Ok(TestSuite::Batch(edited))) => (original, edited),
+        _ => bail!("the edited test suite is broken"),
+    };
+
+    let ok = original.cases.len() == edited_test_suite.cases.len()
+        && original
+            .cases
+            .iter()
+            .zip(&edited_test_suite.cases)
+            .enumerate()
+            .all(|(i, (original, edited))| {
+                let expected_out = outputs
+                    .iter()
+                    .find(|(j, _)| *j == i)
+                    .map(|(_, output)| &**output)
+                    .or(original.out.as_deref());
+                original.name == edited.name
+                    && original.r#in == edited.r#in
+                    && expected_out == edited.out.as_deref()
+            });
+    ensure!(ok, "the edited test suite is broken");
+
+    Ok(edited)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::bless::set_outputs;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn set_outputs_keeps_formatting() -> anyhow::Result<()> {
+        let yaml = r#"---
+type: Batch
+timelimit: 2s
+match: Lines
+
+cases:
+  # handmade
+  - name: handmade1
+    in: |
+      1 2
+  - in: "3 4\n"
+    name: handmade2
+    out: |
+      8
+
+  - name: handmade3
+    in: |
+      5 6
+
+extend: []
+"#;
+
+        let outputs = [
+            (0, "3\n".to_owned()),
+            (1, "7\n".to_owned()),
+            (2, "11 ".to_owned()),
+        ];
+
+        assert_eq!(
+            r#"---
+type: Batch
+timelimit: 2s
+match: Lines
+
+cases:
+  # handmade
+  - name: handmade1
+    in: |
+      1 2
+    out: |
+      3
+  - in: "3 4\n"
+    name: handmade2
+    out: |
+      7
+
+  - name: handmade3
+    in: |
+      5 6
+    out: "11 "
+
+extend: []
+"#,
+            set_outputs(yaml, &outputs)?,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn set_outputs_rejects_flow_style() {
+        let yaml = r#"---
+type: Batch
+timelimit: 2s
+match: Lines
+cases: [{ in: "1 2\n" }]
+extend: []
+"#;
+        assert!(set_outputs(yaml, &[(0, "3\n".to_owned())]).is_err());
+    }
+}
@@ -0,0 +1,84 @@
+use anyhow::Context as _;
+use camino::Utf8Path;
+use itertools::Itertools as _;
+use serde::Deserialize;
+use snowchains_core::testsuite::{CheckerShell, Match};
+
+/// An external program that judges the output.
+///
+/// The checker receives paths to the input, the expected output, and the actual output as the last
+/// three arguments. If `testlib` is `true`, they are given in the order of testlib checkers:
+/// `<input> <actual output> <expected output>`.
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct Checker {
+    pub(crate) cmd: Vec<String>,
+    #[serde(default)]
+    pub(crate) testlib: bool,
+}
+
+impl Checker {
+    /// Converts this into a `Match::Checker`, which is run with Bash by `snowchains_core`.
+    pub(crate) fn to_match(&self, cwd: &Utf8Path) -> anyhow::Result<Match> {
+        let (program, args) = self
+            .cmd
+            .split_first()
+            .with_context(|| "`checker.cmd` is empty")?;
+
+        let paths = if self.testlib {
+            r#""$INPUT" "$ACTUAL_OUTPUT" "${EXPECTED_OUTPUT:-/dev/null}""#
+        } else {
+            r#""$INPUT" "${EXPECTED_OUTPUT:-/dev/null}" "$ACTUAL_OUTPUT""#
+        };
+
+        let cmd = format!(
+            "cd {} && {} {}",
+            shell_escape::unix::escape(cwd.as_str().into()),
+            itertools::chain(Some(program), args)
+                .map(|s| shell_escape::unix::escape(s.into()))
+                .format(" "),
+            paths,
+        );
+
+        Ok(Match::Checker {
+            cmd,
+            shell: CheckerShell::Bash,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::checker::Checker;
+    use pretty_assertions::assert_eq;
+    use snowchains_core::testsuite::{CheckerShell, Match};
+
+    #[test]
+    fn to_match() -> anyhow::Result<()> {
+        let checker = Checker {
+            cmd: vec!["./checker".to_owned(), "--quiet".to_owned()],
+            testlib: false,
+        };
+        assert_eq!(
+            Match::Checker {
+                cmd: r#"cd '/a b/testcases' && ./checker --quiet "$INPUT" "${EXPECTED_OUTPUT:-/dev/null}" "$ACTUAL_OUTPUT""#
+                    .to_owned(),
+                shell: CheckerShell::Bash,
+            },
+            checker.to_match("/a b/testcases".into())?,
+        );
+
+        let checker = Checker {
+            cmd: vec!["./checker".to_owned()],
+            testlib: true,
+        };
+        assert_eq!(
+            Match::Checker {
+                cmd: r#"cd /testcases && ./checker "$INPUT" "$ACTUAL_OUTPUT" "${EXPECTED_OUTPUT:-/dev/null}""#
+                    .to_owned(),
+                shell: CheckerShell::Bash,
+            },
+            checker.to_match("/testcases".into())?,
+        );
+        Ok(())
+    }
+}
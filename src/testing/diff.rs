@@ -0,0 +1,376 @@
+use itertools::{EitherOrBoth, Itertools as _};
+use snowchains_core::{
+    color_spec,
+    testsuite::{DeterministicExpectedOutput, PositiveFinite},
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io,
+    ops::Range,
+};
+use termcolor::{Color, ColorSpec, WriteColor};
+
+/// Number of matching lines shown before and after each differing line.
+const CONTEXT: usize = 2;
+/// Number of differing lines shown at most.
+const MAX_DIFFERING_LINES: usize = 10;
+/// Number of characters shown at most for each line.
+const WIDTH: usize = 80;
+
+#[derive(Debug, Clone, Copy)]
+enum Granularity {
+    Chars,
+    /// Words are compared with the tolerance if `float` is `Some((relative_error, absolute_error))`.
+    Words {
+        float: Option<(f64, f64)>,
+    },
+}
+
+impl Granularity {
+    fn words_match(self, s1: &str, s2: &str) -> bool {
+        match self {
+            Self::Words {
+                float: Some((relative_error, absolute_error)),
+            } => float_words_match(s1, s2, relative_error, absolute_error),
+            _ => s1 == s2,
+        }
+    }
+}
+
+pub(super) fn float_words_match(
+    s1: &str,
+    s2: &str,
+    relative_error: f64,
+    absolute_error: f64,
+) -> bool {
+    match (s1.parse::<f64>(), s2.parse::<f64>()) {
+        (Ok(v1), Ok(v2)) => {
+            (v1 - v2).abs() <= absolute_error || ((v1 - v2) / v2).abs() <= relative_error
+        }
+        _ => s1 == s2,
+    }
+}
+
+/// The differing parts of a line.
+#[derive(Default, Debug, PartialEq)]
+struct LineDiff {
+    /// Ranges of characters in the expected line.
+    expected: Vec<Range<usize>>,
+    /// Ranges of characters in the actual line.
+    actual: Vec<Range<usize>>,
+    /// 0-based character offset of the first mismatch.
+    column: usize,
+}
+
+/// Writes where `actual` differs from `expected`, line by line with the context around them.
+///
+/// Lines are compared in the same way as `match` of the test suite.
+pub(super) fn write_diff(
+    mut wtr: impl WriteColor,
+    expected: &DeterministicExpectedOutput,
+    actual: &str,
+) -> io::Result<()> {
+    let (granularity, expected, exact) = match expected {
+        DeterministicExpectedOutput::Pass => return Ok(()),
+        DeterministicExpectedOutput::Exact { text } => (Granularity::Chars, text, true),
+        DeterministicExpectedOutput::Lines { text } => (Granularity::Chars, text, false),
+        DeterministicExpectedOutput::SplitWhitespace { text } => {
+            (Granularity::Words { float: None }, text, false)
+        }
+        DeterministicExpectedOutput::Float {
+            text,
+            relative_error,
+            absolute_error,
+        } => {
+            let relative_error = relative_error.map(PositiveFinite::get).unwrap_or(0.0);
+            let absolute_error = absolute_error.map(PositiveFinite::get).unwrap_or(0.0);
+            let float = Some((relative_error, absolute_error));
+            (Granularity::Words { float }, text, false)
+        }
+    };
+
+    let expected_lines = split_lines(expected, exact);
+    let actual_lines = split_lines(actual, exact);
+    let num_lines = expected_lines.len().max(actual_lines.len());
+
+    let diffs = expected_lines
+        .iter()
+        .zip_longest(&actual_lines)
+        .enumerate()
+        .flat_map(|(i, zip)| {
+            let diff = match zip {
+                EitherOrBoth::Both(expected, actual) => diff_line(granularity, expected, actual)?,
+                EitherOrBoth::Left(expected) => {
+                    diff_line(granularity, expected, "").unwrap_or_default()
+                }
+                EitherOrBoth::Right(actual) => {
+                    diff_line(granularity, "", actual).unwrap_or_default()
+                }
+            };
+            Some((i, diff))
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    wtr.set_color(color_spec!(Bold, Fg(Color::Magenta)))?;
+    writeln!(wtr, "diff (-expected +actual):")?;
+    wtr.reset()?;
+
+    let (&first, first_diff) = match diffs.iter().next() {
+        Some(first) => first,
+        None => {
+            wtr.set_color(color_spec!(Bold, Fg(Color::Yellow)))?;
+            if expected.ends_with('\n') && !actual.ends_with('\n') {
+                writeln!(wtr, "the actual output does not end with a newline")?;
+            } else if !expected.ends_with('\n') && actual.ends_with('\n') {
+                writeln!(wtr, "the actual output ends with an extra newline")?;
+            } else {
+                writeln!(wtr, "no differing lines")?;
+            }
+            return wtr.reset();
+        }
+    };
+
+    writeln!(
+        wtr,
+        "first mismatch at line {}, column {} ({} of {} lines differ)",
+        first + 1,
+        first_diff.column + 1,
+        diffs.len(),
+        num_lines,
+    )?;
+
+    let shown = diffs
+        .keys()
+        .take(MAX_DIFFERING_LINES)
+        .flat_map(|&i| i.saturating_sub(CONTEXT)..(i + CONTEXT + 1).min(num_lines))
+        .collect::<BTreeSet<_>>();
+    let gutter = num_lines.to_string().len();
+
+    let mut prev = None;
+    let mut num_shown_diffs = 0;
+
+    for &i in &shown {
+        if i > prev.map_or(0, |prev| prev + 1) {
+            writeln!(wtr, "{:>gutter$}", "⋮", gutter = gutter + 1)?;
+        }
+        prev = Some(i);
+
+        if let Some(diff) = diffs.get(&i) {
+            num_shown_diffs += 1;
+            // Both lines are cut at the same place so that they are aligned.
+            let start = diff.column.saturating_sub(WIDTH / 4);
+            for (sign, color, lines, highlights) in [
+                ('-', Color::Red, &expected_lines, &diff.expected),
+                ('+', Color::Green, &actual_lines, &diff.actual),
+            ] {
+                if let Some(line) = lines.get(i) {
+                    write_line(
+                        &mut wtr,
+                        sign,
+                        Some(color),
+                        i,
+                        gutter,
+                        line,
+                        highlights,
+                        start,
+                    )?;
+                }
+            }
+        } else {
+            write_line(&mut wtr, ' ', None, i, gutter, actual_lines[i], &[], 0)?;
+        }
+    }
+
+    if matches!(prev, Some(prev) if prev + 1 < num_lines) {
+        writeln!(wtr, "{:>gutter$}", "⋮", gutter = gutter + 1)?;
+    }
+
+    if num_shown_diffs < diffs.len() {
+        wtr.set_color(color_spec!(Bold, Fg(Color::Yellow)))?;
+        writeln!(
+            wtr,
+            "{} more differing line(s) not shown",
+            diffs.len() - num_shown_diffs,
+        )?;
+        wtr.reset()?;
+    }
+    Ok(())
+}
+
+fn split_lines(text: &str, exact: bool) -> Vec<&str> {
+    if exact {
+        text.split_terminator('\n').collect()
+    } else {
+        text.lines().collect()
+    }
+}
+
+/// Compares a line. Returns `None` if they match.
+#[allow(clippy::single_range_in_vec_init)]
+fn diff_line(granularity: Granularity, expected: &str, actual: &str) -> Option<LineDiff> {
+    match granularity {
+        Granularity::Chars => {
+            if expected == actual {
+                return None;
+            }
+            let expected = expected.chars().collect::<Vec<_>>();
+            let actual = actual.chars().collect::<Vec<_>>();
+            let prefix = expected
+                .iter()
+                .zip(&actual)
+                .take_while(|(c1, c2)| c1 == c2)
+                .count();
+            let suffix = expected[prefix..]
+                .iter()
+                .rev()
+                .zip(actual[prefix..].iter().rev())
+                .take_while(|(c1, c2)| c1 == c2)
+                .count();
+            Some(LineDiff {
+                expected: vec![prefix..expected.len() - suffix],
+                actual: vec![prefix..actual.len() - suffix],
+                column: prefix,
+            })
+        }
+        Granularity::Words { .. } => {
+            let mut diff = LineDiff {
+                expected: vec![],
+                actual: vec![],
+                column: 0,
+            };
+            let mut column = None;
+
+            for zip in words(expected).into_iter().zip_longest(words(actual)) {
+                match zip {
+                    EitherOrBoth::Both((range1, word1), (range2, word2)) => {
+                        if !granularity.words_match(&word1, &word2) {
+                            column.get_or_insert(range2.start);
+                            diff.expected.push(range1);
+                            diff.actual.push(range2);
+                        }
+                    }
+                    EitherOrBoth::Left((range1, _)) => {
+                        column.get_or_insert(actual.chars().count());
+                        diff.expected.push(range1);
+                    }
+                    EitherOrBoth::Right((range2, _)) => {
+                        column.get_or_insert(range2.start);
+                        diff.actual.push(range2);
+                    }
+                }
+            }
+
+            diff.column = column?;
+            Some(diff)
+        }
+    }
+}
+
+/// Splits `line` into whitespace-separated words with their ranges in characters.
+fn words(line: &str) -> Vec<(Range<usize>, String)> {
+    let mut words = vec![];
+    for (is_word, group) in &line
+        .chars()
+        .enumerate()
+        .group_by(|(_, c)| !c.is_whitespace())
+    {
+        if is_word {
+            let group = group.collect::<Vec<_>>();
+            let range = group[0].0..group[group.len() - 1].0 + 1;
+            words.push((range, group.into_iter().map(|(_, c)| c).collect()));
+        }
+    }
+    words
+}
+
+#[allow(clippy::too_many_arguments)]
+fn write_line(
+    mut wtr: impl WriteColor,
+    sign: char,
+    color: Option<Color>,
+    index: usize,
+    gutter: usize,
+    line: &str,
+    highlights: &[Range<usize>],
+    start: usize,
+) -> io::Result<()> {
+    let chars = line.chars().collect::<Vec<_>>();
+    let end = (start + WIDTH).min(chars.len());
+    let start = start.min(end);
+
+    wtr.set_color(ColorSpec::new().set_fg(color))?;
+    write!(wtr, "{}{:>gutter$} | ", sign, index + 1, gutter = gutter)?;
+    if start > 0 {
+        write!(wtr, "…")?;
+    }
+
+    for (highlighted, group) in &(start..end).group_by(|j| highlights.iter().any(|r| r.contains(j)))
+    {
+        wtr.set_color(
+            ColorSpec::new()
+                .set_fg(color)
+                .set_bold(highlighted)
+                .set_underline(highlighted),
+        )?;
+        for j in group {
+            match chars[j] {
+                c if c.is_control() => write!(wtr, "{}", c.escape_default())?,
+                c => write!(wtr, "{c}")?,
+            }
+        }
+    }
+
+    wtr.set_color(ColorSpec::new().set_fg(color))?;
+    if end < chars.len() {
+        write!(wtr, "…")?;
+    }
+    wtr.reset()?;
+    writeln!(wtr)
+}
+
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use crate::testing::diff::{diff_line, Granularity, LineDiff};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn diff_line_chars() {
+        assert_eq!(None, diff_line(Granularity::Chars, "1 2 3", "1 2 3"));
+        assert_eq!(
+            Some(LineDiff {
+                expected: vec![2..4],
+                actual: vec![2..4],
+                column: 2,
+            }),
+            diff_line(Granularity::Chars, "1 23 4", "1 32 4"),
+        );
+    }
+
+    #[test]
+    fn diff_line_words() {
+        let words = Granularity::Words { float: None };
+        assert_eq!(None, diff_line(words, "1  2 3", " 1 2 3"));
+        assert_eq!(
+            Some(LineDiff {
+                expected: vec![2..3, 4..5],
+                actual: vec![3..4],
+                column: 3,
+            }),
+            diff_line(words, "1 2 3", "1  9"),
+        );
+
+        let float = Granularity::Words {
+            float: Some((1e-6, 1e-6)),
+        };
+        assert_eq!(None, diff_line(float, "0.5 1.0", "0.5000001 1"));
+        assert_eq!(
+            Some(LineDiff {
+                expected: vec![4..7],
+                actual: vec![4..7],
+                column: 4,
+            }),
+            diff_line(float, "0.5 1.0", "0.5 1.1"),
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use anyhow::{bail, Context as _};
+use globset::{Glob, GlobMatcher};
+use regex::Regex;
+use std::collections::{BTreeSet, HashSet};
+
+/// Test cases to run, specified with `--testcases` and `--skip`.
+///
+/// Each pattern is a glob (e.g. `sample*`), or a regular expression if it is surrounded by `/`
+/// (e.g. `/^0[0-9]_/`). A pattern without any wildcards is an exact name. Test cases without names
+/// are always selected.
+#[derive(Default, Debug)]
+pub(crate) struct TestCaseFilter {
+    select: Option<Vec<Pattern>>,
+    skip: Vec<Pattern>,
+}
+
+#[derive(Debug)]
+struct Pattern {
+    source: String,
+    matcher: Matcher,
+}
+
+#[derive(Debug)]
+enum Matcher {
+    Exact,
+    Glob(GlobMatcher),
+    Regex(Regex),
+}
+
+impl Pattern {
+    fn new(source: &str) -> anyhow::Result<Self> {
+        let matcher = match source.strip_prefix('/').and_then(|s| s.strip_suffix('/')) {
+            Some(regex) => Matcher::Regex(
+                Regex::new(regex).with_context(|| format!("invalid regex: {source:?}"))?,
+            ),
+            None if !source.contains(['*', '?', '[', '{', '\\']) => Matcher::Exact,
+            None => Matcher::Glob(
+                Glob::new(source)
+                    .with_context(|| format!("invalid glob: {source:?}"))?
+                    .compile_matcher(),
+            ),
+        };
+        Ok(Self {
+            source: source.to_owned(),
+            matcher,
+        })
+    }
+
+    fn is_match(&self, name: &str) -> bool {
+        match &self.matcher {
+            Matcher::Exact => self.source == name,
+            Matcher::Glob(glob) => glob.is_match(name),
+            Matcher::Regex(regex) => regex.is_match(name),
+        }
+    }
+}
+
+impl TestCaseFilter {
+    pub(crate) fn new(select: Option<&[String]>, skip: &[String]) -> anyhow::Result<Self> {
+        let patterns = |ss: &[String]| ss.iter().map(|s| Pattern::new(s)).collect();
+        Ok(Self {
+            select: select.map(patterns).transpose()?,
+            skip: patterns(skip)?,
+        })
+    }
+
+    /// Selects only the test case named `name`, taken literally.
+    pub(crate) fn exact(name: &str) -> Self {
+        Self {
+            select: Some(vec![Pattern {
+                source: name.to_owned(),
+                matcher: Matcher::Exact,
+            }]),
+            skip: vec![],
+        }
+    }
+
+    /// Returns the names of `--testcases` if all of them are exact names.
+    pub(crate) fn exact_names(&self) -> Option<HashSet<&str>> {
+        self.select
+            .as_ref()?
+            .iter()
+            .map(|p| matches!(p.matcher, Matcher::Exact).then_some(&*p.source))
+            .collect()
+    }
+
+    /// Retains the selected test cases.
+    ///
+    /// Fails if any pattern of `--testcases` matches none of them.
+    pub(crate) fn apply<T>(
+        &self,
+        test_cases: Vec<T>,
+        name: impl Fn(&T) -> Option<&str>,
+    ) -> anyhow::Result<Vec<T>> {
+        let mut unmatched = self
+            .select
+            .iter()
+            .flatten()
+            .map(|p| &*p.source)
+            .collect::<BTreeSet<_>>();
+
+        let test_cases = test_cases
+            .into_iter()
+            .filter(|test_case| {
+                let name = match name(test_case) {
+                    Some(name) => name,
+                    None => return true,
+                };
+                let selected = match &self.select {
+                    Some(select) => {
+                        let matched = select
+                            .iter()
+                            .filter(|p| p.is_match(name))
+                            .collect::<Vec<_>>();
+                        for pattern in &matched {
+                            unmatched.remove(&*pattern.source);
+                        }
+                        !matched.is_empty()
+                    }
+                    None => true,
+                };
+                selected && !self.skip.iter().any(|p| p.is_match(name))
+            })
+            .collect();
+
+        if !unmatched.is_empty() {
+            bail!("No such test cases: {:?}", unmatched);
+        }
+        Ok(test_cases)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::filter::TestCaseFilter;
+    use maplit::btreeset;
+    use pretty_assertions::assert_eq;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn apply() -> anyhow::Result<()> {
+        let names = || vec!["sample1", "sample2", "01_small", "02_large", "02_max"];
+        let strings = |ss: &[&str]| ss.iter().map(|&s| s.to_owned()).collect::<Vec<_>>();
+        let apply = |select: Option<&[&str]>, skip: &[&str]| -> anyhow::Result<Vec<&str>> {
+            let select = select.map(strings);
+            TestCaseFilter::new(select.as_deref(), &strings(skip))?
+                .apply(names(), |&name| Some(name))
+        };
+
+        assert_eq!(names(), apply(None, &[])?);
+        assert_eq!(["sample2"], *apply(Some(&["sample2"]), &[])?);
+        assert_eq!(
+            ["sample1", "sample2", "02_large", "02_max"],
+            *apply(Some(&["sample*", "02_*"]), &[])?,
+        );
+        assert_eq!(
+            ["01_small", "02_max"],
+            *apply(Some(&["/^0/"]), &["*large"])?
+        );
+        assert_eq!(["sample1", "sample2"], *apply(None, &["/_/"])?);
+        assert!(apply(Some(&["sample*", "03_*"]), &[]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn exact_names() -> anyhow::Result<()> {
+        let strings = |ss: &[&str]| ss.iter().map(|&s| s.to_owned()).collect::<Vec<_>>();
+        let exact_names = |select: &[&str]| -> anyhow::Result<_> {
+            let filter = TestCaseFilter::new(Some(&strings(select)), &[])?;
+            Ok(filter.exact_names().map(|names| {
+                names
+                    .into_iter()
+                    .map(ToOwned::to_owned)
+                    .collect::<BTreeSet<_>>()
+            }))
+        };
+
+        assert_eq!(
+            Some(btreeset!("sample1".to_owned(), "02_max".to_owned())),
+            exact_names(&["sample1", "02_max"])?,
+        );
+        assert_eq!(None, exact_names(&["sample1", "02_*"])?);
+        assert_eq!(None, exact_names(&["/sample1/"])?);
+        assert_eq!(None, TestCaseFilter::new(None, &[])?.exact_names());
+        Ok(())
+    }
+}
@@ -0,0 +1,197 @@
+use super::report::{ReportSuite, ReportVerdict};
+use crate::shell::Shell;
+use camino::{Utf8Path, Utf8PathBuf};
+use cargo_metadata as cm;
+use prettytable::{row, Row};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+use std::{collections::BTreeMap, time::SystemTime};
+use strum::{EnumString, EnumVariantNames};
+
+/// Run to compare with by `--compare`.
+#[derive(EnumString, EnumVariantNames, strum::Display, Clone, Copy, Debug, PartialEq)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Baseline {
+    /// The last run of the same `bin`.
+    Previous,
+    /// The fastest accepted run of each test case of the same `bin`.
+    Best,
+}
+
+/// A line of the timing history.
+#[derive(Serialize, Deserialize, Debug)]
+struct Record {
+    time: String,
+    package: String,
+    bin: String,
+    /// SHA-256 of the source file.
+    source_hash: String,
+    cases: Vec<RecordCase>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct RecordCase {
+    name: Option<String>,
+    verdict: ReportVerdict,
+    elapsed_ms: u64,
+}
+
+/// Returns the path to the timing history of the workspace.
+pub(crate) fn history_path(metadata: &cm::Metadata) -> Utf8PathBuf {
+    metadata
+        .target_directory
+        .join("compete")
+        .join("timing-history.jsonl")
+}
+
+/// Appends the elapsed times in `suite` to the history, comparing them with `compare` first.
+///
+/// A test case is regarded as a regression if it got slower by more than 10% and 10 ms.
+pub(crate) fn record(
+    history_path: &Utf8Path,
+    bin: &cm::Target,
+    suite: &ReportSuite,
+    compare: Option<Baseline>,
+    shell: &mut Shell,
+) -> anyhow::Result<()> {
+    if suite.cases.is_empty() {
+        return Ok(());
+    }
+
+    let source_hash = Sha256::digest(&crate::fs::read(&bin.src_path)?)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect::<String>();
+
+    let record = Record {
+        time: humantime::format_rfc3339_seconds(SystemTime::now()).to_string(),
+        package: suite.package.clone(),
+        bin: suite.bin.clone(),
+        source_hash,
+        cases: suite
+            .cases
+            .iter()
+            .map(|c| RecordCase {
+                name: c.name.clone(),
+                verdict: c.verdict,
+                elapsed_ms: c.elapsed_ms,
+            })
+            .collect(),
+    };
+
+    if let Some(baseline) = compare {
+        let history = crate::fs::read_jsonl::<Record, _>(history_path)?
+            .into_iter()
+            .filter(|r| r.package == record.package && r.bin == record.bin)
+            .collect::<Vec<_>>();
+        print_comparison(&record, &history, baseline, shell)?;
+    }
+
+    crate::fs::append_jsonl(history_path, record)
+}
+
+fn print_comparison(
+    record: &Record,
+    history: &[Record],
+    baseline: Baseline,
+    shell: &mut Shell,
+) -> anyhow::Result<()> {
+    let baselines = match baseline {
+        Baseline::Previous => {
+            let previous = match history.last() {
+                Some(previous) => previous,
+                None => {
+                    shell.warn(format!("no previous runs of `{}` to compare", record.bin))?;
+                    return Ok(());
+                }
+            };
+            shell.status(
+                "Comparing",
+                format!(
+                    "with the previous run at {} ({})",
+                    previous.time,
+                    if previous.source_hash == record.source_hash {
+                        "same source"
+                    } else {
+                        "different source"
+                    },
+                ),
+            )?;
+            previous
+                .cases
+                .iter()
+                .map(|c| (c.name.clone(), c.elapsed_ms))
+                .collect::<BTreeMap<_, _>>()
+        }
+        Baseline::Best => {
+            let num_same_source = history
+                .iter()
+                .filter(|r| r.source_hash == record.source_hash)
+                .count();
+            shell.status(
+                "Comparing",
+                format!(
+                    "with the best of {} run(s), {} of which are of the same source",
+                    history.len(),
+                    num_same_source,
+                ),
+            )?;
+            let mut acc = BTreeMap::new();
+            for case in history.iter().flat_map(|r| &r.cases) {
+                if case.verdict == ReportVerdict::Ac {
+                    let best = acc.entry(case.name.clone()).or_insert(case.elapsed_ms);
+                    *best = (*best).min(case.elapsed_ms);
+                }
+            }
+            acc
+        }
+    };
+
+    let header = match baseline {
+        Baseline::Previous => "Previous",
+        Baseline::Best => "Best",
+    };
+    let mut rows = vec![row!["Test case", "Elapsed", header, "Delta", ""]];
+    let mut regressions = vec![];
+
+    for case in &record.cases {
+        let name = format!("{:?}", case.name.as_deref().unwrap_or(""));
+        let (base, delta, is_regression) = match baselines.get(&case.name) {
+            Some(&base) => {
+                let delta = case.elapsed_ms as i64 - base as i64;
+                let is_regression = delta > 10 && delta * 10 > base as i64;
+                if is_regression {
+                    regressions.push((name.clone(), base, case.elapsed_ms));
+                }
+                let delta = if base == 0 {
+                    format!("{delta:+} ms")
+                } else {
+                    format!(
+                        "{:+} ms ({:+.0}%)",
+                        delta,
+                        delta as f64 / base as f64 * 100.0
+                    )
+                };
+                (format!("{base} ms"), delta, is_regression)
+            }
+            None => ("-".to_owned(), "-".to_owned(), false),
+        };
+
+        rows.push(Row::from(vec![
+            name,
+            format!("{} ms", case.elapsed_ms),
+            base,
+            delta,
+            if is_regression { "regression" } else { "" }.to_owned(),
+        ]));
+    }
+
+    shell.print_table(&rows)?;
+
+    for (name, base, elapsed) in regressions {
+        shell.warn(format!(
+            "{name}: {elapsed} ms is slower than the {baseline} run ({base} ms)",
+        ))?;
+    }
+    Ok(())
+}
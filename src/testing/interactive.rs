@@ -0,0 +1,400 @@
+use anyhow::Context as _;
+use serde::Deserialize;
+use snowchains_core::{color_spec, judge::CommandExpression};
+use std::{
+    ffi::OsString,
+    io::{self, Read, Write},
+    path::Path,
+    process::{ExitStatus, Stdio},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+use termcolor::{Color, WriteColor};
+
+/// Fields of `TestSuite::Interactive` that `snowchains_core` does not know.
+#[derive(Deserialize, Default, Debug)]
+pub(crate) struct InteractiveTestSuiteExtra {
+    #[serde(default)]
+    pub(crate) interactor: Option<Vec<String>>,
+    #[serde(default)]
+    pub(crate) cases: Vec<InteractiveTestCase>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct InteractiveTestCase {
+    pub(crate) name: Option<String>,
+    #[serde(default)]
+    pub(crate) r#in: String,
+}
+
+pub(crate) struct Interactor<'a> {
+    pub(crate) args: &'a [String],
+    pub(crate) cwd: &'a Path,
+}
+
+#[derive(Debug)]
+pub(crate) struct JudgeOutcome {
+    pub(crate) verdicts: Vec<Verdict>,
+    /// Number of the test cases skipped by `fail_fast`.
+    pub(crate) num_skipped: usize,
+}
+
+impl JudgeOutcome {
+    pub(crate) fn print_pretty(
+        &self,
+        mut wtr: impl WriteColor,
+        display_limit: Option<usize>,
+    ) -> io::Result<()> {
+        for (i, verdict) in self.verdicts.iter().enumerate() {
+            if i > 0 {
+                writeln!(wtr)?;
+            }
+
+            write!(
+                wtr,
+                "{}/{} ({:?}) ",
+                i + 1,
+                self.verdicts.len() + self.num_skipped,
+                verdict.test_case_name.as_deref().unwrap_or(""),
+            )?;
+
+            wtr.set_color(color_spec!(Bold, Fg(verdict.kind.summary_color())))?;
+            writeln!(wtr, "{}", verdict.summary())?;
+            wtr.reset()?;
+
+            if verdict.kind == VerdictKind::Accepted {
+                continue;
+            }
+
+            let mut write_text = |header: &str, text: &str| -> io::Result<()> {
+                wtr.set_color(color_spec!(Bold, Fg(Color::Magenta)))?;
+                writeln!(wtr, "{header}")?;
+                wtr.reset()?;
+
+                if text.is_empty() {
+                    wtr.set_color(color_spec!(Bold, Fg(Color::Yellow)))?;
+                    writeln!(wtr, "EMPTY")?;
+                    return wtr.reset();
+                }
+
+                if matches!(display_limit, Some(l) if l < text.len()) {
+                    wtr.set_color(color_spec!(Bold, Fg(Color::Yellow)))?;
+                    writeln!(wtr, "{} B", text.len())?;
+                    return wtr.reset();
+                }
+
+                wtr.write_all(text.as_ref())?;
+
+                if !text.ends_with('\n') {
+                    wtr.set_color(color_spec!(Fg(Color::Yellow)))?;
+                    writeln!(wtr, "⏎")?;
+                    wtr.reset()?;
+                }
+                Ok(())
+            };
+
+            write_text("input:", &verdict.input)?;
+            write_text("transcript:", &verdict.transcript())?;
+            if !verdict.stderr.is_empty() {
+                write_text("stderr:", &verdict.stderr)?;
+            }
+            if !verdict.interactor_stderr.is_empty() {
+                write_text("interactor stderr:", &verdict.interactor_stderr)?;
+            }
+        }
+
+        wtr.flush()
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Verdict {
+    pub(crate) test_case_name: Option<String>,
+    pub(crate) kind: VerdictKind,
+    pub(crate) elapsed: Duration,
+    pub(crate) input: String,
+    pub(crate) transcript: Vec<(Direction, String)>,
+    pub(crate) stderr: String,
+    pub(crate) interactor_stderr: String,
+}
+
+impl Verdict {
+    pub(crate) fn summary(&self) -> String {
+        let elapsed = self.elapsed.as_millis();
+        match &self.kind {
+            VerdictKind::Accepted => format!("Accepted ({elapsed} ms)"),
+            VerdictKind::WrongAnswer(status) => {
+                format!("Wrong Answer ({elapsed} ms, interactor {status})")
+            }
+            VerdictKind::RuntimeError(status) => format!("Runtime Error ({elapsed} ms, {status})"),
+            VerdictKind::StackOverflow(_) => {
+                format!("Runtime Error ({elapsed} ms, stack overflow)")
+            }
+            VerdictKind::TimelimitExceeded(timelimit) => {
+                format!("Timelimit Exceeded ({} ms)", timelimit.as_millis())
+            }
+        }
+    }
+
+    pub(crate) fn transcript(&self) -> String {
+        let mut acc = "".to_owned();
+        for (direction, text) in &self.transcript {
+            for line in text.lines() {
+                acc += match direction {
+                    Direction::FromSolution => "> ",
+                    Direction::FromInteractor => "< ",
+                };
+                acc += line;
+                acc += "\n";
+            }
+        }
+        acc
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum VerdictKind {
+    Accepted,
+    WrongAnswer(ExitStatus),
+    RuntimeError(ExitStatus),
+    /// A runtime error caused by a stack overflow.
+    StackOverflow(ExitStatus),
+    TimelimitExceeded(Duration),
+}
+
+impl VerdictKind {
+    fn summary_color(&self) -> Color {
+        match self {
+            Self::Accepted => Color::Green,
+            Self::TimelimitExceeded(_) => Color::Red,
+            Self::WrongAnswer(_) | Self::RuntimeError(_) | Self::StackOverflow(_) => Color::Yellow,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Direction {
+    FromSolution,
+    FromInteractor,
+}
+
+/// Runs `program` against `interactor` for each test case.
+///
+/// The interactor is called in the same manner as testlib: `<interactor> <input> <output>`. Its
+/// stdin is connected to the stdout of the program, and vice versa.
+///
+/// With `fail_fast`, the test cases after the first failure are skipped.
+pub(crate) fn judge(
+    cmd: &CommandExpression,
+    interactor: &Interactor<'_>,
+    timelimit: Option<Duration>,
+    test_cases: &[InteractiveTestCase],
+    fail_fast: bool,
+) -> anyhow::Result<JudgeOutcome> {
+    let (interactor_program, interactor_args) = interactor
+        .args
+        .split_first()
+        .with_context(|| "`interactor` is empty")?;
+    let interactor_program =
+        crate::process::which(interactor_program, cwd_as_utf8(interactor.cwd)?)?;
+
+    super::listen_ctrl_c()?;
+
+    let tempdir = tempfile::Builder::new()
+        .prefix("cargo-compete-interactive-")
+        .tempdir()?;
+
+    let mut verdicts: Vec<Verdict> = vec![];
+
+    for (i, test_case) in test_cases.iter().enumerate() {
+        if fail_fast && verdicts.iter().any(|v| v.kind != VerdictKind::Accepted) {
+            break;
+        }
+
+        let input_path = tempdir.path().join(format!("{i}-input"));
+        let output_path = tempdir.path().join(format!("{i}-output"));
+        crate::fs::write(&input_path, &test_case.r#in)?;
+
+        let mut interactor_child = std::process::Command::new(&interactor_program)
+            .args(interactor_args)
+            .args(&[OsString::from(&input_path), OsString::from(&output_path)])
+            .current_dir(interactor.cwd)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("could not execute `{}`", interactor_program.display()))?;
+
+        let started = Instant::now();
+
+        let mut solution_child = match std::process::Command::new(&cmd.program)
+            .args(&cmd.args)
+            .current_dir(&cmd.cwd)
+            .envs(&cmd.env)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(err) => {
+                let _ = interactor_child.kill();
+                let _ = interactor_child.wait();
+                return Err(err).with_context(|| {
+                    format!("could not execute `{}`", cmd.program.to_string_lossy())
+                });
+            }
+        };
+
+        let transcript = Arc::new(Mutex::new(vec![]));
+
+        let relays = vec![
+            relay(
+                solution_child.stdout.take().unwrap(),
+                interactor_child.stdin.take().unwrap(),
+                Direction::FromSolution,
+                transcript.clone(),
+            ),
+            relay(
+                interactor_child.stdout.take().unwrap(),
+                solution_child.stdin.take().unwrap(),
+                Direction::FromInteractor,
+                transcript.clone(),
+            ),
+        ];
+        let solution_stderr = read_to_end(solution_child.stderr.take().unwrap());
+        let interactor_stderr = read_to_end(interactor_child.stderr.take().unwrap());
+
+        let deadline = timelimit.map(|t| started + t + Duration::from_millis(100));
+
+        let solution_status = match super::wait_with_deadline(&mut solution_child, deadline) {
+            Ok(exited) => exited.map(|super::Exited { status, .. }| status),
+            Err(err) => {
+                let _ = interactor_child.kill();
+                let _ = interactor_child.wait();
+                return Err(err.into());
+            }
+        };
+        let elapsed = started.elapsed();
+        let interactor_status = match super::wait_with_deadline(&mut interactor_child, deadline) {
+            Ok(exited) => exited.map(|super::Exited { status, .. }| status),
+            Err(err) => {
+                if solution_status.is_none() {
+                    let _ = solution_child.kill();
+                    let _ = solution_child.wait();
+                }
+                return Err(err.into());
+            }
+        };
+
+        // The exited ones are already reaped.
+        if solution_status.is_none() {
+            let _ = solution_child.kill();
+            let _ = solution_child.wait();
+        }
+        if interactor_status.is_none() {
+            let _ = interactor_child.kill();
+            let _ = interactor_child.wait();
+        }
+
+        for relay in relays {
+            relay.join().unwrap();
+        }
+
+        let solution_stderr = solution_stderr.join().unwrap();
+
+        // The interactor's verdict takes priority over the program's exit status, since the
+        // program is likely to die of a broken pipe after the interactor gives up.
+        let kind = match (timelimit, solution_status, interactor_status) {
+            (Some(timelimit), None, _) | (Some(timelimit), _, None) => {
+                VerdictKind::TimelimitExceeded(timelimit)
+            }
+            (Some(timelimit), ..) if timelimit < elapsed => {
+                VerdictKind::TimelimitExceeded(timelimit)
+            }
+            (_, _, Some(status)) if !status.success() => VerdictKind::WrongAnswer(status),
+            (_, Some(status), _) if !status.success() => {
+                if super::is_stack_overflow(&solution_stderr) {
+                    VerdictKind::StackOverflow(status)
+                } else {
+                    VerdictKind::RuntimeError(status)
+                }
+            }
+            _ => VerdictKind::Accepted,
+        };
+
+        let transcript = Arc::try_unwrap(transcript)
+            .unwrap()
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .fold(vec![], |mut acc: RawTranscript, (direction, chunk)| {
+                match acc.last_mut() {
+                    Some((last, bytes)) if *last == direction => bytes.extend(chunk),
+                    _ => acc.push((direction, chunk)),
+                }
+                acc
+            })
+            .into_iter()
+            .map(|(direction, bytes)| (direction, String::from_utf8_lossy(&bytes).into_owned()))
+            .collect();
+
+        verdicts.push(Verdict {
+            test_case_name: test_case.name.clone(),
+            kind,
+            elapsed,
+            input: test_case.r#in.clone(),
+            transcript,
+            stderr: solution_stderr,
+            interactor_stderr: interactor_stderr.join().unwrap(),
+        });
+    }
+
+    let num_skipped = test_cases.len() - verdicts.len();
+
+    tempdir.close()?;
+    return Ok(JudgeOutcome {
+        verdicts,
+        num_skipped,
+    });
+
+    type RawTranscript = Vec<(Direction, Vec<u8>)>;
+
+    fn cwd_as_utf8(cwd: &Path) -> anyhow::Result<&camino::Utf8Path> {
+        camino::Utf8Path::from_path(cwd)
+            .with_context(|| format!("non UTF-8 path: {:?}", cwd.display()))
+    }
+
+    fn relay(
+        mut from: impl Read + Send + 'static,
+        mut to: impl Write + Send + 'static,
+        direction: Direction,
+        transcript: Arc<Mutex<RawTranscript>>,
+    ) -> thread::JoinHandle<()> {
+        thread::spawn(move || {
+            let mut buf = [0; 4096];
+            let mut connected = true;
+            while let Ok(n) = from.read(&mut buf) {
+                if n == 0 {
+                    break;
+                }
+                transcript
+                    .lock()
+                    .unwrap()
+                    .push((direction, buf[..n].to_owned()));
+                if connected {
+                    connected = to.write_all(&buf[..n]).and_then(|()| to.flush()).is_ok();
+                }
+            }
+        })
+    }
+
+    fn read_to_end(mut from: impl Read + Send + 'static) -> thread::JoinHandle<String> {
+        thread::spawn(move || {
+            let mut buf = vec![];
+            let _ = from.read_to_end(&mut buf);
+            String::from_utf8_lossy(&buf).into_owned()
+        })
+    }
+}
@@ -0,0 +1,459 @@
+use super::{
+    batch::{expected_stdout, VerdictKind},
+    filter::TestCaseFilter,
+    stress::{judge_one, text_file_paths},
+};
+use crate::{
+    config::{CargoCompeteConfigTestChecker, CargoCompeteConfigTestRun},
+    project::PackageExt as _,
+    shell::Shell,
+};
+use anyhow::{bail, Context as _};
+use az::SaturatingAs as _;
+use cargo_metadata as cm;
+use human_size::{Byte, Size};
+use indicatif::{ProgressBar, ProgressStyle};
+use itertools::Itertools as _;
+use liquid::object;
+use snowchains_core::testsuite::{ExpectedOutput, TestSuite};
+use std::{cmp, iter, mem, path::Path};
+use strum::{EnumString, EnumVariantNames};
+use url::Url;
+
+pub(crate) struct Args<'a> {
+    pub(crate) metadata: &'a cm::Metadata,
+    pub(crate) member: &'a cm::Package,
+    pub(crate) bin: &'a cm::Target,
+    pub(crate) bin_alias: &'a str,
+    pub(crate) reference: Option<&'a cm::Target>,
+    pub(crate) cargo_compete_config_test_suite: &'a liquid::Template,
+    pub(crate) problem_url: &'a Url,
+    pub(crate) toolchain: Option<&'a str>,
+    pub(crate) checker: Option<&'a CargoCompeteConfigTestChecker>,
+    pub(crate) run: &'a CargoCompeteConfigTestRun,
+    pub(crate) release: bool,
+    pub(crate) source: Source<'a>,
+    pub(crate) granularity: Granularity,
+    pub(crate) fixed_lines: usize,
+    pub(crate) display_limit: Size,
+    pub(crate) diff: bool,
+    pub(crate) cookies_path: &'a Path,
+    pub(crate) cargo_compete_exe: Option<&'a Path>,
+    pub(crate) shell: &'a mut Shell,
+}
+
+pub(crate) enum Source<'a> {
+    File(&'a Path),
+    TestCase(&'a str),
+}
+
+/// Unit of the input to remove.
+#[derive(EnumString, EnumVariantNames, strum::Display, Clone, Copy, Debug, PartialEq)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Granularity {
+    Lines,
+    Tokens,
+}
+
+/// Shrinks a failing input with delta debugging.
+///
+/// A candidate is regarded as "failing" if `bin` gets the same verdict as for the original input.
+/// If `reference` is given, its output is used as the expected output and candidates on which it
+/// fails are skipped. Otherwise, a Wrong Answer can only be minimized with a checker, since the
+/// expected output for the original input does not hold for the candidates.
+pub(crate) fn minimize(args: Args<'_>) -> anyhow::Result<()> {
+    let Args {
+        metadata,
+        member,
+        bin,
+        bin_alias,
+        reference,
+        cargo_compete_config_test_suite,
+        problem_url,
+        toolchain,
+        checker,
+        run,
+        release,
+        source,
+        granularity,
+        fixed_lines,
+        display_limit,
+        diff,
+        cookies_path,
+        cargo_compete_exe,
+        shell,
+    } = args;
+
+    let test_suite_path = super::test_suite_path(
+        &metadata.workspace_root,
+        member.manifest_dir(),
+        cargo_compete_config_test_suite,
+        &bin.name,
+        bin_alias,
+        problem_url,
+        shell,
+    )?;
+
+    let test_suite_dir = test_suite_path.parent().unwrap();
+
+    let vars = object!({
+        "manifest_dir": member.manifest_dir(),
+        "bin_name": &bin.name,
+        "bin_alias": bin_alias,
+    });
+
+    let mut test_suite = match crate::fs::read_yaml(&test_suite_path)? {
+        TestSuite::Batch(test_suite) => test_suite,
+        TestSuite::Interactive(_) => bail!("`minimize` does not support `Interactive` problems"),
+        TestSuite::Unsubmittable => bail!("this is `Unsubmittable` problem"),
+    };
+
+    let super::BatchTestSuiteExtra {
+        checker: checker_in_test_suite,
+        memorylimit,
+        validator,
+    } = crate::fs::read_yaml(&test_suite_path)?;
+
+    if let Some(checker) =
+        super::resolve_checker(checker_in_test_suite, test_suite_dir, checker, &vars, shell)?
+    {
+        test_suite.r#match = checker.to_match(test_suite_dir)?;
+    }
+
+    let memory_limit = super::resolve_memory_limit(memorylimit.as_deref(), &test_suite_path, None)?;
+    let validator = super::resolve_validator(
+        validator,
+        test_suite_dir,
+        metadata,
+        member,
+        bin,
+        toolchain,
+        release,
+        shell,
+    )?;
+
+    let (name, input, expected) = match source {
+        Source::File(path) => {
+            let name = path
+                .file_stem()
+                .with_context(|| format!("invalid path: {}", path.display()))?
+                .to_string_lossy()
+                .into_owned();
+            (name, crate::fs::read_to_string(path)?, None)
+        }
+        Source::TestCase(name) => {
+            let test_case = super::load_test_cases(
+                &test_suite,
+                test_suite_dir,
+                &TestCaseFilter::exact(name),
+                problem_url,
+                cookies_path,
+                &metadata.workspace_root,
+                shell,
+            )?
+            .pop()
+            .expect("should have been checked by `load_test_cases`");
+            let expected = match &test_case.output {
+                ExpectedOutput::Deterministic(expected) => expected_stdout(expected),
+                ExpectedOutput::Checker { text, .. } => text.as_deref(),
+            }
+            .map(ToOwned::to_owned);
+            (name.to_owned(), test_case.input.to_string(), expected)
+        }
+    };
+
+    let artifacts = super::build_all(
+        metadata,
+        member,
+        &iter::once(bin).chain(reference).collect::<Vec<_>>(),
+        toolchain,
+        release,
+        shell,
+    )?;
+    let (bin, reference) = (&artifacts[0], artifacts.get(1));
+    let cmd = super::command_expression(
+        bin,
+        run,
+        &vars,
+        &metadata.workspace_root,
+        problem_url,
+        cargo_compete_exe,
+    )?;
+
+    // The reference solution may crash on candidates, which are not necessarily valid.
+    let run_reference = |input: &str, quiet: bool| -> anyhow::Result<String> {
+        let reference = crate::process::process(reference.unwrap())
+            .pipe_input(Some(input))
+            .cwd(&metadata.workspace_root);
+        if quiet {
+            reference.null_stderr()
+        } else {
+            reference
+        }
+        .read()
+    };
+
+    // Candidates are built with `join`, so the original input is judged in the same form.
+    let (head, elems) = split(&input, granularity, fixed_lines);
+    let original_len = elems.len();
+    let input = join(&head, &elems);
+
+    let expected = if reference.is_some() {
+        Some(run_reference(&input, false).with_context(|| "the reference solution failed")?)
+    } else {
+        expected
+    };
+
+    let outcome = judge_one(
+        &cmd,
+        &test_suite,
+        test_suite_dir,
+        memory_limit,
+        validator.as_ref(),
+        &name,
+        &input,
+        expected.as_deref(),
+    )?;
+
+    let original_verdict = match &*outcome.verdicts {
+        [verdict] if verdict.kind == VerdictKind::Accepted => {
+            bail!("`{}` does not fail. nothing to minimize", name);
+        }
+        [verdict] if verdict.kind == VerdictKind::InvalidInput => {
+            bail!("`{}` is rejected by the validator", name);
+        }
+        [verdict]
+            if verdict.kind == VerdictKind::WrongAnswer
+                && reference.is_none()
+                && !matches!(verdict.expected, ExpectedOutput::Checker { .. }) =>
+        {
+            bail!(
+                "`{}` gets Wrong Answer, which cannot be judged for smaller inputs without a \
+                 checker. specify `--reference`",
+                name,
+            );
+        }
+        [verdict] => mem::discriminant(&verdict.kind),
+        _ => unreachable!(),
+    };
+
+    shell.status(
+        "Minimizing",
+        format!("{} ({} {})", name, elems.len(), granularity),
+    )?;
+
+    let pb = ProgressBar::new_spinner();
+    pb.set_draw_target(shell.progress_draw_target());
+    pb.set_style(ProgressStyle::default_spinner().template("{spinner} {msg:bold}"));
+    pb.enable_steady_tick(50);
+
+    let mut num_runs = 0;
+    let mut last = (expected, outcome);
+
+    let elems = ddmin(elems, |candidate| {
+        num_runs += 1;
+        pb.set_message(&format!(
+            "#{} ({} {})",
+            num_runs,
+            candidate.len(),
+            granularity,
+        ));
+
+        let input = join(&head, candidate);
+
+        let expected = if reference.is_some() {
+            match run_reference(&input, true) {
+                Ok(expected) => Some(expected),
+                // Probably the input is no longer valid.
+                Err(_) => return Ok(false),
+            }
+        } else {
+            last.0.clone()
+        };
+
+        let outcome = judge_one(
+            &cmd,
+            &test_suite,
+            test_suite_dir,
+            memory_limit,
+            validator.as_ref(),
+            &name,
+            &input,
+            expected.as_deref(),
+        )?;
+
+        let fails = matches!(
+            &*outcome.verdicts,
+            [verdict] if mem::discriminant(&verdict.kind) == original_verdict
+        );
+        if fails {
+            last = (expected, outcome);
+        }
+        Ok(fails)
+    })?;
+
+    pb.finish_and_clear();
+
+    let (expected, outcome) = last;
+
+    let (in_path, out_path) = text_file_paths(
+        &test_suite_path,
+        &test_suite,
+        bin_alias,
+        &format!("{name}-min"),
+        shell,
+    )?;
+    crate::fs::write(&in_path, join(&head, &elems))?;
+    if let (Some(expected), Some(_)) = (&expected, reference) {
+        crate::fs::write(&out_path, expected)?;
+    }
+
+    let display_limit = display_limit.into::<Byte>().value().saturating_as();
+
+    writeln!(shell.err())?;
+    outcome.print_pretty(shell.err(), Some(display_limit), diff)?;
+    writeln!(shell.err())?;
+
+    shell.status(
+        "Finished",
+        format!(
+            "{} -> {} {} in {} run{}",
+            original_len,
+            elems.len(),
+            granularity,
+            num_runs,
+            if num_runs == 1 { "" } else { "s" },
+        ),
+    )?;
+    shell.status(
+        "Saved",
+        if reference.is_some() {
+            format!("{in_path} and {out_path}")
+        } else {
+            in_path.into_string()
+        },
+    )?;
+    Ok(())
+}
+
+/// Splits `input` into the first `fixed_lines` lines and the rest.
+///
+/// Each element of the rest is tagged with the line number so that [`join`] can restore the line
+/// structure.
+fn split(
+    input: &str,
+    granularity: Granularity,
+    fixed_lines: usize,
+) -> (String, Vec<(usize, &str)>) {
+    let mut lines = input.lines().enumerate();
+
+    let head = lines
+        .by_ref()
+        .take(fixed_lines)
+        .map(|(_, line)| format!("{line}\n"))
+        .join("");
+
+    let elems = match granularity {
+        Granularity::Lines => lines.collect(),
+        Granularity::Tokens => lines
+            .flat_map(|(i, line)| line.split_whitespace().map(move |token| (i, token)))
+            .collect(),
+    };
+
+    (head, elems)
+}
+
+fn join(head: &str, elems: &[(usize, &str)]) -> String {
+    let mut acc = head.to_owned();
+    for (_, line) in &elems.iter().group_by(|(i, _)| *i) {
+        acc += &line.map(|(_, s)| *s).join(" ");
+        acc += "\n";
+    }
+    acc
+}
+
+/// Finds a 1-minimal subsequence of `elems` that still `fails`.
+fn ddmin<T: Clone>(
+    mut elems: Vec<T>,
+    mut fails: impl FnMut(&[T]) -> anyhow::Result<bool>,
+) -> anyhow::Result<Vec<T>> {
+    let mut n = 2;
+
+    while elems.len() >= 2 {
+        let chunk_len = elems.len().div_ceil(n);
+        let chunks = (0..elems.len())
+            .step_by(chunk_len)
+            .map(|start| start..cmp::min(start + chunk_len, elems.len()))
+            .collect::<Vec<_>>();
+
+        let mut reduced = false;
+
+        for chunk in &chunks {
+            if fails(&elems[chunk.clone()])? {
+                elems = elems[chunk.clone()].to_vec();
+                n = 2;
+                reduced = true;
+                break;
+            }
+        }
+
+        if !reduced && n > 2 {
+            for chunk in &chunks {
+                let complement = [&elems[..chunk.start], &elems[chunk.end..]].concat();
+                if fails(&complement)? {
+                    elems = complement;
+                    n = cmp::max(n - 1, 2);
+                    reduced = true;
+                    break;
+                }
+            }
+        }
+
+        if !reduced {
+            if n >= elems.len() {
+                break;
+            }
+            n = cmp::min(n * 2, elems.len());
+        }
+    }
+
+    if elems.len() == 1 && fails(&[])? {
+        elems.clear();
+    }
+    Ok(elems)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::minimize::{ddmin, join, split, Granularity};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn ddmin_finds_minimal_subsequence() -> anyhow::Result<()> {
+        let mut num_runs = 0;
+        let minimized = ddmin((0..100).collect(), |candidate| {
+            num_runs += 1;
+            Ok(candidate.contains(&13) && candidate.contains(&71))
+        })?;
+        assert_eq!(vec![13, 71], minimized);
+        assert!(num_runs < 100);
+        Ok(())
+    }
+
+    #[test]
+    fn split_and_join() {
+        let input = "3\n1 2 3\n4 5\n";
+
+        let (head, elems) = split(input, Granularity::Lines, 1);
+        assert_eq!("3\n", head);
+        assert_eq!(vec![(1, "1 2 3"), (2, "4 5")], elems);
+        assert_eq!(input, join(&head, &elems));
+
+        let (head, elems) = split(input, Granularity::Tokens, 1);
+        assert_eq!(
+            vec![(1, "1"), (1, "2"), (1, "3"), (2, "4"), (2, "5")],
+            elems,
+        );
+        assert_eq!("3\n1 3\n5\n", join(&head, &[elems[0], elems[2], elems[4]]));
+    }
+}
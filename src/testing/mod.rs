@@ -0,0 +1,964 @@
+mod batch;
+mod bench;
+pub(crate) mod bless;
+mod checker;
+mod diff;
+pub(crate) mod filter;
+pub(crate) mod history;
+mod interactive;
+pub(crate) mod minimize;
+pub(crate) mod payload;
+pub(crate) mod report;
+pub(crate) mod run;
+pub(crate) mod stress;
+mod suite_yaml;
+pub(crate) mod testcase;
+pub(crate) mod validator;
+mod watch;
+
+pub(crate) use self::batch::default_jobs;
+
+use self::{
+    checker::Checker,
+    filter::TestCaseFilter,
+    interactive::{InteractiveTestCase, InteractiveTestSuiteExtra, Interactor},
+    report::{ReportCase, ReportSuite},
+    validator::{Validator, ValidatorSpec},
+};
+use crate::{
+    config::{CargoCompeteConfigTestChecker, CargoCompeteConfigTestRun},
+    project::PackageExt as _,
+    shell::Shell,
+};
+use anyhow::{anyhow, ensure, Context as _};
+use az::SaturatingAs as _;
+use camino::{Utf8Path, Utf8PathBuf};
+use cargo_metadata as cm;
+use human_size::{Byte, Size};
+use itertools::Itertools as _;
+use liquid::object;
+use maplit::btreemap;
+use once_cell::sync::OnceCell;
+use serde::Deserialize;
+use snowchains_core::{
+    judge::CommandExpression,
+    testsuite::{
+        BatchTestCase, BatchTestSuite, InteractiveTestSuite, PartialBatchTestCase, TestSuite,
+    },
+    web::PlatformKind,
+};
+use std::{
+    collections::BTreeMap,
+    env, io,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    process::{Child, ExitStatus},
+    sync::{
+        atomic::{self, AtomicBool},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+use url::Url;
+
+pub(crate) struct Args<'a> {
+    pub(crate) metadata: &'a cm::Metadata,
+    pub(crate) member: &'a cm::Package,
+    pub(crate) bin: &'a cm::Target,
+    pub(crate) bin_alias: &'a str,
+    pub(crate) cargo_compete_config_test_suite: &'a liquid::Template,
+    pub(crate) problem_url: &'a Url,
+    pub(crate) toolchain: Option<&'a str>,
+    pub(crate) interactor: Option<&'a [liquid::Template]>,
+    pub(crate) checker: Option<&'a CargoCompeteConfigTestChecker>,
+    pub(crate) run: &'a CargoCompeteConfigTestRun,
+    pub(crate) release: bool,
+    pub(crate) memory_limit: Option<Size>,
+    pub(crate) jobs: NonZeroUsize,
+    /// Number of times to run the test cases to measure the elapsed time.
+    pub(crate) repeat: NonZeroUsize,
+    /// Ratio to the timelimit for the median elapsed time to be warned with `repeat`.
+    pub(crate) tle_risk: f64,
+    pub(crate) test_case_filter: &'a TestCaseFilter,
+    /// Stops running the test cases after the first failure.
+    pub(crate) fail_fast: bool,
+    pub(crate) display_limit: Size,
+    /// Shows the differences for wrong answers instead of the whole outputs.
+    pub(crate) diff: bool,
+    /// Skips building `bin` if given.
+    pub(crate) artifact: Option<&'a Utf8Path>,
+    pub(crate) cookies_path: &'a Path,
+    pub(crate) cargo_compete_exe: Option<&'a Path>,
+    pub(crate) shell: &'a mut Shell,
+}
+
+/// Tests `bin` and prints the results.
+///
+/// Failing test cases do not make this return `Err`. Call [`ReportSuite::error_on_fail`] for it.
+pub(crate) fn test(args: Args<'_>) -> anyhow::Result<ReportSuite> {
+    let Args {
+        metadata,
+        member,
+        bin,
+        bin_alias,
+        cargo_compete_config_test_suite,
+        problem_url,
+        toolchain,
+        interactor,
+        checker,
+        run,
+        release,
+        memory_limit,
+        jobs,
+        repeat,
+        tle_risk,
+        test_case_filter,
+        fail_fast,
+        display_limit,
+        diff,
+        artifact,
+        cookies_path,
+        cargo_compete_exe,
+        shell,
+    } = args;
+
+    let display_limit = display_limit.into::<Byte>().value().saturating_as();
+
+    let report_suite = |cases: Vec<ReportCase>| -> ReportSuite {
+        ReportSuite::new(
+            &member.name,
+            &bin.name,
+            bin_alias,
+            problem_url.as_str(),
+            cases,
+        )
+    };
+
+    let build = |shell: &mut Shell| -> anyhow::Result<Utf8PathBuf> {
+        match artifact {
+            Some(artifact) => Ok(artifact.to_owned()),
+            None => build(metadata, member, bin, toolchain, release, shell),
+        }
+    };
+
+    let test_suite_path = test_suite_path(
+        &metadata.workspace_root,
+        member.manifest_dir(),
+        cargo_compete_config_test_suite,
+        &bin.name,
+        bin_alias,
+        problem_url,
+        shell,
+    )?;
+
+    let test_suite_dir = test_suite_path.parent().unwrap();
+
+    let vars = object!({
+        "manifest_dir": member.manifest_dir(),
+        "bin_name": &bin.name,
+        "bin_alias": bin_alias,
+    });
+
+    let test_suite = crate::fs::read_yaml(&test_suite_path)?;
+
+    let (test_cases, memory_limit, validator) = match test_suite {
+        TestSuite::Batch(mut test_suite) => {
+            let BatchTestSuiteExtra {
+                checker: checker_in_test_suite,
+                memorylimit,
+                validator,
+            } = crate::fs::read_yaml(&test_suite_path)?;
+
+            if let Some(checker) =
+                resolve_checker(checker_in_test_suite, test_suite_dir, checker, &vars, shell)?
+            {
+                test_suite.r#match = checker.to_match(test_suite_dir)?;
+            }
+
+            let test_cases = load_test_cases(
+                &test_suite,
+                test_suite_dir,
+                test_case_filter,
+                problem_url,
+                cookies_path,
+                &metadata.workspace_root,
+                shell,
+            )?;
+            let memory_limit =
+                resolve_memory_limit(memorylimit.as_deref(), &test_suite_path, memory_limit)?;
+            let validator = resolve_validator(
+                validator,
+                test_suite_dir,
+                metadata,
+                member,
+                bin,
+                toolchain,
+                release,
+                shell,
+            )?;
+            (test_cases, memory_limit, validator)
+        }
+        TestSuite::Interactive(InteractiveTestSuite { timelimit }) => {
+            let InteractiveTestSuiteExtra {
+                interactor: interactor_in_test_suite,
+                cases,
+            } = crate::fs::read_yaml(&test_suite_path)?;
+
+            let interactor_args = if let Some(args) = interactor_in_test_suite {
+                args
+            } else if let Some(args) = interactor {
+                render_args(args, &vars)?
+            } else {
+                shell.warn(
+                    "this is an `Interactive` problem. specify `interactor` in the test suite or \
+                     `test.interactor` in compete.toml to test it",
+                )?;
+                return Ok(report_suite(vec![]));
+            };
+
+            let test_cases = filter_interactive_test_cases(cases, test_case_filter)?;
+
+            if repeat.get() > 1 {
+                shell.warn("`--repeat` is not supported for `Interactive` problems")?;
+            }
+
+            let artifact = build(shell)?;
+
+            let outcome = interactive::judge(
+                &command_expression(
+                    &artifact,
+                    run,
+                    &vars,
+                    &metadata.workspace_root,
+                    problem_url,
+                    cargo_compete_exe,
+                )?,
+                &Interactor {
+                    args: &interactor_args,
+                    cwd: test_suite_dir.as_ref(),
+                },
+                timelimit,
+                &test_cases,
+                fail_fast,
+            )?;
+
+            writeln!(shell.err())?;
+            outcome.print_pretty(shell.err(), Some(display_limit))?;
+            warn_skipped(outcome.num_skipped, shell)?;
+            return Ok(report_suite(
+                outcome
+                    .verdicts
+                    .iter()
+                    .map(|v| ReportCase::from_interactive(v, display_limit))
+                    .collect(),
+            ));
+        }
+        TestSuite::Unsubmittable => {
+            shell.warn("this is `Unsubmittable` problem")?;
+            (vec![], None, None)
+        }
+    };
+
+    let artifact = build(shell)?;
+    let cmd = command_expression(
+        &artifact,
+        run,
+        &vars,
+        &metadata.workspace_root,
+        problem_url,
+        cargo_compete_exe,
+    )?;
+
+    let outcome = batch::judge(
+        shell.progress_draw_target(),
+        &cmd,
+        &test_cases,
+        memory_limit,
+        validator.as_ref(),
+        jobs,
+        fail_fast,
+    )?;
+
+    writeln!(shell.err())?;
+    outcome.print_pretty(shell.err(), Some(display_limit), diff)?;
+    warn_skipped(outcome.num_skipped, shell)?;
+
+    if repeat.get() > 1 && !test_cases.is_empty() {
+        writeln!(shell.err())?;
+        bench::bench(
+            &cmd,
+            &test_cases,
+            &outcome,
+            memory_limit,
+            jobs,
+            repeat,
+            tle_risk,
+            shell,
+        )?;
+    }
+    Ok(report_suite(
+        outcome
+            .verdicts
+            .iter()
+            .map(|v| ReportCase::from_batch(v, display_limit))
+            .collect(),
+    ))
+}
+
+/// Runs [`test`] every time the source code or the test suite changes, until interrupted.
+///
+/// `on_finish` is called with the results of each run. Errors from it, including build errors,
+/// are printed and do not stop watching.
+pub(crate) fn watch(
+    args: Args<'_>,
+    mut on_finish: impl FnMut(ReportSuite, &mut Shell) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let Args {
+        metadata,
+        member,
+        bin,
+        bin_alias,
+        cargo_compete_config_test_suite,
+        problem_url,
+        toolchain,
+        interactor,
+        checker,
+        run,
+        release,
+        memory_limit,
+        jobs,
+        repeat,
+        tle_risk,
+        test_case_filter,
+        fail_fast,
+        display_limit,
+        diff,
+        artifact,
+        cookies_path,
+        cargo_compete_exe,
+        shell,
+    } = args;
+
+    let test_suite_path = test_suite_path(
+        &metadata.workspace_root,
+        member.manifest_dir(),
+        cargo_compete_config_test_suite,
+        &bin.name,
+        bin_alias,
+        problem_url,
+        shell,
+    )?;
+
+    let mut paths = watch::source_paths(metadata, member, bin);
+    paths.push(test_suite_path.with_file_name(bin_alias));
+    paths.push(test_suite_path);
+    let mut watcher = watch::Watcher::new(paths);
+
+    loop {
+        shell.clear_screen()?;
+
+        let result = test(Args {
+            metadata,
+            member,
+            bin,
+            bin_alias,
+            cargo_compete_config_test_suite,
+            problem_url,
+            toolchain,
+            interactor,
+            checker,
+            run,
+            release,
+            memory_limit,
+            jobs,
+            repeat,
+            tle_risk,
+            test_case_filter,
+            fail_fast,
+            display_limit,
+            diff,
+            artifact,
+            cookies_path,
+            cargo_compete_exe,
+            shell,
+        })
+        .and_then(|suite| on_finish(suite, shell));
+
+        check_ctrl_c()?;
+        if let Err(err) = &result {
+            shell.error(err)?;
+        }
+        shell.status("Watching", "for changes. press Ctrl-C to quit")?;
+
+        watcher.wait()?;
+    }
+}
+
+fn warn_skipped(num_skipped: usize, shell: &mut Shell) -> io::Result<()> {
+    if num_skipped > 0 {
+        shell.warn(format!(
+            "skipped {num_skipped} test case(s) after the first failure",
+        ))?;
+    }
+    Ok(())
+}
+
+/// Loads the test cases, downloading the system test cases if necessary.
+fn load_test_cases(
+    test_suite: &BatchTestSuite,
+    test_suite_dir: &Utf8Path,
+    test_case_filter: &TestCaseFilter,
+    problem_url: &Url,
+    cookies_path: &Path,
+    workspace_root: &Utf8Path,
+    shell: &mut Shell,
+) -> anyhow::Result<Vec<BatchTestCase>> {
+    let names = test_case_filter.exact_names();
+
+    // The extend files and the system test cases are not read if the selected test cases are all
+    // in `cases`.
+    let without_extend;
+    let test_suite = match &names {
+        Some(names)
+            if names.iter().all(|&name| {
+                test_suite
+                    .cases
+                    .iter()
+                    .any(|c| c.name.as_deref() == Some(name))
+            }) =>
+        {
+            without_extend = BatchTestSuite {
+                timelimit: test_suite.timelimit,
+                r#match: test_suite.r#match.clone(),
+                cases: test_suite.cases.clone(),
+                extend: vec![],
+            };
+            &without_extend
+        }
+        _ => test_suite,
+    };
+
+    let test_cases =
+        test_suite.load_test_cases(test_suite_dir.as_ref(), names, |override_problem_url| {
+            fn read(path: &Path) -> anyhow::Result<Arc<str>> {
+                crate::fs::read_to_string(path).map(Into::into)
+            }
+
+            let problem_url = override_problem_url.unwrap_or(problem_url);
+
+            let system_test_cases_dir =
+                crate::web::retrieve_testcases::system_test_cases_dir(problem_url)?;
+
+            let text_files = |dir_name: &str| -> anyhow::Result<Vec<_>> {
+                let paths = crate::fs::read_dir(system_test_cases_dir.join(dir_name))?;
+                Ok(paths
+                    .into_iter()
+                    .filter(|p| p.extension() == Some("txt".as_ref()))
+                    .map(|p| {
+                        let s = p
+                            .file_stem()
+                            .expect("should not be empty")
+                            .to_string_lossy()
+                            .into_owned();
+                        (s, p)
+                    })
+                    .collect())
+            };
+
+            if !system_test_cases_dir.join("in").exists() {
+                crate::web::retrieve_testcases::dl_only_system_test_cases(
+                    problem_url,
+                    cookies_path,
+                    workspace_root,
+                    shell,
+                )?;
+            }
+
+            let mut system_test_cases: BTreeMap<_, (Option<_>, Option<_>)> = btreemap!();
+
+            for (name, path) in text_files("in")? {
+                system_test_cases.entry(name).or_default().0 = Some(read(&path)?);
+            }
+            for (name, path) in text_files("out")? {
+                system_test_cases.entry(name).or_default().1 = Some(read(&path)?);
+            }
+
+            Ok(system_test_cases
+                .into_iter()
+                .flat_map(|(name, (r#in, out))| {
+                    let r#in = r#in?;
+                    Some(PartialBatchTestCase {
+                        name: Some(name),
+                        r#in,
+                        out,
+                        timelimit: None,
+                        r#match: None,
+                    })
+                })
+                .collect())
+        })?;
+    test_case_filter.apply(test_cases, |test_case| test_case.name.as_deref())
+}
+
+/// Fields of `TestSuite::Batch` that `snowchains_core` does not know.
+///
+/// Read this once per test suite and pass the fields to the `resolve_*` functions.
+#[derive(Deserialize, Default, Debug)]
+struct BatchTestSuiteExtra {
+    #[serde(default)]
+    checker: Option<Checker>,
+    /// e.g. `1024 MiB`
+    #[serde(default)]
+    memorylimit: Option<String>,
+    #[serde(default)]
+    validator: Option<ValidatorSpec>,
+}
+
+/// Returns the checker in the test suite, or `test.checker` in compete.toml if it exists.
+fn resolve_checker(
+    checker_in_test_suite: Option<Checker>,
+    test_suite_dir: &Utf8Path,
+    checker: Option<&CargoCompeteConfigTestChecker>,
+    vars: &liquid::Object,
+    shell: &mut Shell,
+) -> anyhow::Result<Option<Checker>> {
+    if let Some(checker) = checker_in_test_suite {
+        return Ok(Some(checker));
+    }
+
+    let CargoCompeteConfigTestChecker { cmd, testlib } = match checker {
+        Some(checker) => checker,
+        None => return Ok(None),
+    };
+
+    let cmd = render_args(cmd, vars)?;
+    // `test.checker` is shared by all of the problems. Ignore it unless it exists.
+    let program = match cmd.first() {
+        Some(program) => program,
+        None => {
+            shell.warn("ignoring `test.checker`: `cmd` is empty")?;
+            return Ok(None);
+        }
+    };
+    if let Err(err) = crate::process::which(program, test_suite_dir) {
+        shell.warn(format!("ignoring `test.checker`: {err}"))?;
+        return Ok(None);
+    }
+    Ok(Some(Checker {
+        cmd,
+        testlib: *testlib,
+    }))
+}
+
+/// Returns the validator in the test suite, or `validator` of `bin` in
+/// `package.metadata.cargo-compete`, building it if it is a `bin`/`example`.
+#[allow(clippy::too_many_arguments)]
+fn resolve_validator(
+    validator: Option<ValidatorSpec>,
+    test_suite_dir: &Utf8Path,
+    metadata: &cm::Metadata,
+    member: &cm::Package,
+    bin: &cm::Target,
+    toolchain: Option<&str>,
+    release: bool,
+    shell: &mut Shell,
+) -> anyhow::Result<Option<Validator>> {
+    let package_metadata = member.read_package_metadata(shell)?;
+
+    let validator = validator.or_else(|| {
+        let (_, bin) = package_metadata.bin_like_by_name_or_alias(&bin.name).ok()?;
+        bin.validator.clone().map(ValidatorSpec::Bin)
+    });
+
+    match validator {
+        None => Ok(None),
+        Some(ValidatorSpec::Cmd(cmd)) => {
+            let (program, args) = cmd
+                .split_first()
+                .with_context(|| "`validator.cmd` is empty")?;
+            Ok(Some(Validator {
+                program: crate::process::which(program, test_suite_dir)?,
+                args: args.to_owned(),
+                cwd: test_suite_dir.to_owned(),
+            }))
+        }
+        Some(ValidatorSpec::Bin(name_or_alias)) => {
+            let bin = member.bin_like_target_by_name_or_alias(&package_metadata, &name_or_alias)?;
+            let artifact = build(metadata, member, bin, toolchain, release, shell)?;
+            Ok(Some(Validator {
+                program: artifact.into(),
+                args: vec![],
+                cwd: metadata.workspace_root.clone(),
+            }))
+        }
+    }
+}
+
+/// Returns the memory limit in bytes. `memory_limit` overrides `memorylimit` in the test suite.
+fn resolve_memory_limit(
+    memorylimit: Option<&str>,
+    test_suite_path: &Utf8Path,
+    memory_limit: Option<Size>,
+) -> anyhow::Result<Option<u64>> {
+    let memory_limit = if let Some(memory_limit) = memory_limit {
+        memory_limit
+    } else {
+        match memorylimit {
+            Some(memorylimit) => memorylimit
+                .parse()
+                .map_err(|e| anyhow!("{}", e))
+                .with_context(|| {
+                    format!("invalid `memorylimit` in `{test_suite_path}`: {memorylimit:?}")
+                })?,
+            None => return Ok(None),
+        }
+    };
+    Ok(Some(memory_limit.into::<Byte>().value().saturating_as()))
+}
+
+/// Returns the command to run `artifact` with `test.cmd`, `test.env`, `test.cwd`, and
+/// `test.stack-size` in compete.toml.
+///
+/// `vars` are extended with `artifact`. A relative `cwd` is resolved from `workspace_root`, which
+/// is also the default. On Unix, the command is wrapped with `cargo compete exec-with-stack-size`
+/// if `cargo_compete_exe` is given and `test.stack-size` or the default for the platform exists.
+fn command_expression(
+    artifact: &Utf8Path,
+    run: &CargoCompeteConfigTestRun,
+    vars: &liquid::Object,
+    workspace_root: &Utf8Path,
+    problem_url: &Url,
+    cargo_compete_exe: Option<&Path>,
+) -> anyhow::Result<CommandExpression> {
+    let CargoCompeteConfigTestRun {
+        cmd,
+        env,
+        cwd,
+        stack_size,
+    } = run;
+
+    let mut vars = vars.clone();
+    vars.insert(
+        "artifact".into(),
+        liquid::model::Value::scalar(artifact.to_string()),
+    );
+
+    let cwd = match cwd {
+        Some(cwd) => workspace_root.join(cwd.render(&vars)?),
+        None => workspace_root.to_owned(),
+    };
+
+    let (program, args) = match cmd {
+        Some(cmd) => {
+            let cmd = render_args(cmd, &vars)?;
+            let (program, args) = cmd.split_first().with_context(|| "`test.cmd` is empty")?;
+            let program = crate::process::which(program, &cwd)?;
+            (program.into(), args.iter().map(Into::into).collect())
+        }
+        None => (artifact.into(), vec![]),
+    };
+
+    let stack_size = match stack_size {
+        Some(stack_size) => Some((*stack_size).into::<Byte>().value().saturating_as()),
+        None => default_stack_size(problem_url),
+    };
+
+    let (program, args) = match (stack_size, cargo_compete_exe) {
+        (Some(stack_size), Some(cargo_compete_exe)) if cfg!(unix) => (
+            cargo_compete_exe.into(),
+            ["compete", "exec-with-stack-size", "--stack-size"]
+                .iter()
+                .map(Into::into)
+                .chain([stack_size.to_string().into(), "--".into(), program])
+                .chain(args)
+                .collect(),
+        ),
+        _ => (program, args),
+    };
+
+    Ok(CommandExpression {
+        program,
+        args,
+        cwd: cwd.into(),
+        env: env.iter().map(|(k, v)| (k.into(), v.into())).collect(),
+    })
+}
+
+/// Returns the stack size in bytes for the platform, where deep recursion is allowed.
+fn default_stack_size(problem_url: &Url) -> Option<u64> {
+    match PlatformKind::from_url(problem_url) {
+        // The stack size is only limited by the memory limit, which is usually 1024 MiB.
+        Ok(PlatformKind::Atcoder) => Some(1024 * 1024 * 1024),
+        // Solutions are linked with `--stack=268435456`.
+        Ok(PlatformKind::Codeforces) => Some(256 * 1024 * 1024),
+        _ => None,
+    }
+}
+
+/// Returns whether `stderr` of a Rust program reports a stack overflow.
+///
+/// The message is printed by the guard page handler of the standard library before aborting.
+fn is_stack_overflow(stderr: &str) -> bool {
+    stderr.contains("has overflowed its stack")
+}
+
+fn render_args(args: &[liquid::Template], vars: &liquid::Object) -> anyhow::Result<Vec<String>> {
+    args.iter()
+        .map(|t| t.render(vars).map_err(Into::into))
+        .collect()
+}
+
+fn filter_interactive_test_cases(
+    cases: Vec<InteractiveTestCase>,
+    test_case_filter: &TestCaseFilter,
+) -> anyhow::Result<Vec<InteractiveTestCase>> {
+    // Without any cases, the program is run once with no input.
+    let cases = if cases.is_empty() {
+        vec![InteractiveTestCase {
+            name: None,
+            r#in: "".to_owned(),
+        }]
+    } else {
+        cases
+    };
+    test_case_filter.apply(cases, |test_case| test_case.name.as_deref())
+}
+
+fn build(
+    metadata: &cm::Metadata,
+    member: &cm::Package,
+    bin: &cm::Target,
+    toolchain: Option<&str>,
+    release: bool,
+    shell: &mut Shell,
+) -> anyhow::Result<Utf8PathBuf> {
+    let mut artifacts = build_all(metadata, member, &[bin], toolchain, release, shell)?;
+    Ok(artifacts.remove(0))
+}
+
+/// Builds the `bin`/`example` targets with one `cargo build` and returns the paths to the artifacts.
+pub(crate) fn build_all(
+    metadata: &cm::Metadata,
+    member: &cm::Package,
+    bins: &[&cm::Target],
+    toolchain: Option<&str>,
+    release: bool,
+    shell: &mut Shell,
+) -> anyhow::Result<Vec<Utf8PathBuf>> {
+    let is_example = |bin: &cm::Target| bin.kind == ["example".to_owned()];
+
+    let mut cargo_build = if let Some(toolchain) = toolchain {
+        crate::process::process("rustup").args(&["run", toolchain, "cargo"])
+    } else {
+        crate::process::process(crate::process::cargo_exe()?)
+    }
+    .arg("build");
+
+    for &bin in bins.iter().unique_by(|bin| (&bin.name, is_example(bin))) {
+        cargo_build = cargo_build
+            .arg(if is_example(bin) {
+                "--example"
+            } else {
+                "--bin"
+            })
+            .arg(&bin.name);
+    }
+
+    cargo_build
+        .args(if release { &["--release"] } else { &[] })
+        .arg("--manifest-path")
+        .arg(&member.manifest_path)
+        .cwd(&metadata.workspace_root)
+        .exec_with_shell_status(shell)?;
+
+    bins.iter()
+        .map(|bin| {
+            let artifact = metadata
+                .target_directory
+                .join(if release { "release" } else { "debug" })
+                .join(if is_example(bin) { "examples" } else { "" })
+                .join(&bin.name)
+                .with_extension(env::consts::EXE_EXTENSION);
+
+            ensure!(
+                artifact.exists(),
+                "`cargo build` succeeded but `{}` was not produced. probably this is a bug",
+                artifact,
+            );
+
+            Ok(artifact)
+        })
+        .collect()
+}
+
+/// Set when Ctrl-C is pressed after [`listen_ctrl_c`] is called.
+static CTRL_C: AtomicBool = AtomicBool::new(false);
+
+/// Starts handling Ctrl-C so that the running programs can be killed.
+///
+/// Ctrl-C no longer terminates the process after this is called. Loops should stop with
+/// [`check_ctrl_c`] instead.
+fn listen_ctrl_c() -> io::Result<()> {
+    static LISTENER: OnceCell<()> = OnceCell::new();
+
+    LISTENER.get_or_try_init(|| {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_io()
+            .build()?;
+        thread::spawn(move || {
+            if rt.block_on(tokio::signal::ctrl_c()).is_ok() {
+                CTRL_C.store(true, atomic::Ordering::SeqCst);
+            }
+        });
+        Ok::<_, io::Error>(())
+    })?;
+    Ok(())
+}
+
+/// Returns `Err` if Ctrl-C has been pressed.
+pub(crate) fn check_ctrl_c() -> anyhow::Result<()> {
+    ensure!(!CTRL_C.load(atomic::Ordering::SeqCst), "received Ctrl-C");
+    Ok(())
+}
+
+/// Exit status of a program.
+struct Exited {
+    status: ExitStatus,
+    /// Peak resident set size in bytes. Only available on Linux.
+    peak_memory: Option<u64>,
+}
+
+/// Waits for `child` until `deadline`. Returns `None` if it is still running.
+///
+/// On Ctrl-C, `child` is killed and this returns `Err`.
+fn wait_with_deadline(child: &mut Child, deadline: Option<Instant>) -> io::Result<Option<Exited>> {
+    let started = Instant::now();
+    let mut peak_memory = PeakMemory::default();
+    loop {
+        peak_memory.sample(child);
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(Exited {
+                status,
+                peak_memory: peak_memory.bytes,
+            }));
+        }
+        if CTRL_C.load(atomic::Ordering::SeqCst) {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(io::Error::new(
+                io::ErrorKind::Interrupted,
+                "received Ctrl-C",
+            ));
+        }
+        if matches!(deadline, Some(d) if d <= Instant::now()) {
+            return Ok(None);
+        }
+        // Most programs exit soon, and the peak memory cannot be sampled after that.
+        thread::sleep(if started.elapsed() < Duration::from_millis(20) {
+            Duration::from_micros(50)
+        } else {
+            Duration::from_millis(1)
+        });
+    }
+}
+
+/// `VmHWM` of a child process, which is of the program itself since `exec` starts it over.
+///
+/// It cannot be read after the program exits, so it is sampled while waiting for the program.
+/// What is used after the last sample is not counted.
+#[derive(Default)]
+struct PeakMemory {
+    /// The executable that `bytes` is of. Wrappers such as `exec-with-stack-size` execute another
+    /// one.
+    exe: Option<PathBuf>,
+    bytes: Option<u64>,
+}
+
+impl PeakMemory {
+    #[cfg(target_os = "linux")]
+    fn sample(&mut self, child: &Child) {
+        let proc_dir = PathBuf::from(format!("/proc/{}", child.id()));
+
+        // `exe` is read first so that `status` is not of the previous executable.
+        let sample = (|| {
+            let exe = std::fs::read_link(proc_dir.join("exe")).ok()?;
+            let status = std::fs::read_to_string(proc_dir.join("status")).ok()?;
+            let kib = status
+                .lines()
+                .find_map(|line| line.strip_prefix("VmHWM:"))?
+                .trim()
+                .strip_suffix("kB")?
+                .trim()
+                .parse::<u64>()
+                .ok()?;
+            Some((exe, kib * 1024))
+        })();
+
+        if let Some((exe, bytes)) = sample {
+            // `exec-with-stack-size` has not executed the program yet.
+            if exe.file_stem() == Some("cargo-compete".as_ref()) {
+                return;
+            }
+            if self.exe.as_ref() != Some(&exe) {
+                self.exe = Some(exe);
+                self.bytes = None;
+            }
+            self.bytes = self.bytes.max(Some(bytes));
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn sample(&mut self, _: &Child) {}
+}
+
+pub(crate) fn test_suite_path(
+    workspace_root: &Utf8Path,
+    pkg_manifest_dir: &Utf8Path,
+    cargo_compete_config_test_suite: &liquid::Template,
+    bin_name: &str,
+    bin_alias: &str,
+    problem_url: &Url,
+    shell: &mut Shell,
+) -> anyhow::Result<Utf8PathBuf> {
+    let contest = match PlatformKind::from_url(problem_url) {
+        Ok(PlatformKind::Atcoder) => Some(snowchains_core::web::atcoder_contest_id(problem_url)?),
+        Ok(PlatformKind::Codeforces) => {
+            Some(snowchains_core::web::codeforces_contest_id(problem_url)?.to_string())
+        }
+        _ => None,
+    };
+
+    let vars = object!({
+        "manifest_dir": pkg_manifest_dir,
+        "contest": contest,
+        "bin_name": bin_name,
+        "bin_alias": bin_alias,
+    });
+
+    let vars_including_deprecated = object!({
+        "manifest_dir": pkg_manifest_dir,
+        "contest": contest,
+        "bin_name": bin_name,
+        "bin_alias": bin_alias,
+        "problem": bin_alias,
+    });
+
+    let (test_suite_path, uses_deprecated_vars) = cargo_compete_config_test_suite
+        .render(&vars)
+        .map(|r| (r, false))
+        .or_else(|_| {
+            cargo_compete_config_test_suite
+                .render(&vars_including_deprecated)
+                .map(|r| (r, true))
+        })?;
+    let test_suite_path = Utf8Path::new(&test_suite_path);
+    let test_suite_path = test_suite_path.strip_prefix(".").unwrap_or(test_suite_path);
+
+    if uses_deprecated_vars {
+        shell.warn("deprecated variables used for `.test-suite` in compete.toml")?;
+        shell.warn("- `problem` is deprecated. use `bin_alias` instead.")?;
+    }
+
+    Ok(workspace_root.join(test_suite_path))
+}
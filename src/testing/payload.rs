@@ -0,0 +1,157 @@
+use crate::{
+    config::{
+        CargoCompeteConfig, CargoCompeteConfigSubmit, CargoCompeteConfigSubmitBundle,
+        CargoCompeteConfigSubmitCommand, CargoCompeteConfigSubmitFile,
+    },
+    project::PackageExt as _,
+    shell::Shell,
+};
+use anyhow::{bail, Context as _};
+use camino::{Utf8Path, Utf8PathBuf};
+use cargo_metadata as cm;
+use liquid::object;
+use snowchains_core::web::PlatformKind;
+use url::Url;
+
+/// Produces the code to submit in the way `submit.kind` specifies.
+pub(crate) fn payload(
+    submit: &CargoCompeteConfigSubmit,
+    metadata: &cm::Metadata,
+    member: &cm::Package,
+    bin: &cm::Target,
+    bin_alias: &str,
+    problem_url: &Url,
+    shell: &mut Shell,
+) -> anyhow::Result<String> {
+    match submit {
+        CargoCompeteConfigSubmit::File(CargoCompeteConfigSubmitFile { path, .. }) => {
+            let contest = match PlatformKind::from_url(problem_url) {
+                Ok(PlatformKind::Atcoder) => Some(crate::web::url::atcoder_contest(problem_url)?),
+                Ok(PlatformKind::Codeforces) => {
+                    crate::web::url::codeforces_contest(problem_url).ok()
+                }
+                Ok(PlatformKind::Yukicoder) => crate::web::url::yukicoder_contest(problem_url).ok(),
+                _ => todo!(),
+            };
+            let path = path.render(&object!({
+                "manifest_dir": member.manifest_dir(),
+                "contest": contest,
+                "bin_name": &bin.name,
+                "bin_alias": bin_alias,
+                "src_path": &bin.src_path,
+            }))?;
+            crate::fs::read_to_string(path)
+        }
+        CargoCompeteConfigSubmit::Command(CargoCompeteConfigSubmitCommand { args, .. })
+        | CargoCompeteConfigSubmit::DeprecatedTranspileCommand(CargoCompeteConfigSubmitCommand {
+            args,
+            ..
+        }) => {
+            if args.is_empty() {
+                bail!("`submit.transpile.args` is empty");
+            }
+
+            let vars = object!({ "bin_name": &bin.name });
+
+            let args = args
+                .iter()
+                .map(|t| t.render(&vars))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            crate::process::with_which(&args[0], &metadata.workspace_root)?
+                .args(&args[1..])
+                .read_with_shell_status(shell)
+                .with_context(|| "could not transpile the code")
+        }
+        CargoCompeteConfigSubmit::Bundle(CargoCompeteConfigSubmitBundle { .. }) => {
+            shell.status("Bundling", format!("`{}`", bin.name))?;
+            crate::bundle::bundle(metadata, member, bin)
+                .with_context(|| "could not bundle the code")
+        }
+    }
+}
+
+/// Builds `payload` as a standalone crate and returns the path to the artifact.
+///
+/// The crate is generated in `<target-dir>/compete/submission-payload/<package>` with
+/// `template.new` in compete.toml, and built with `test.toolchain`. It is not a temporary
+/// directory so that the dependencies are not rebuilt every time.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn build(
+    metadata: &cm::Metadata,
+    member: &cm::Package,
+    bin: &cm::Target,
+    payload: &str,
+    cargo_compete_config: &CargoCompeteConfig,
+    cargo_compete_config_path: &Utf8Path,
+    release: bool,
+    shell: &mut Shell,
+) -> anyhow::Result<Utf8PathBuf> {
+    let template = cargo_compete_config.template(cargo_compete_config_path, shell)?;
+    let template_new = template.new.unwrap_or_default();
+
+    let manifest_dir = metadata
+        .target_directory
+        .join("compete")
+        .join("submission-payload")
+        .join(&member.name);
+    let manifest_path = manifest_dir.join("Cargo.toml");
+    let src_path = manifest_dir
+        .join("src")
+        .join("bin")
+        .join(&bin.name)
+        .with_extension("rs");
+
+    let mut manifest = r#"[package]
+name = "submission-payload"
+version = "0.1.0"
+edition = ""
+
+[workspace]
+
+[[bin]]
+name = ""
+path = ""
+"#
+    .parse::<toml_edit::Document>()
+    .unwrap();
+
+    manifest["package"]["edition"] = toml_edit::value(match template_new.edition {
+        Some(edition) => edition.to_string(),
+        None => bin.edition.clone(),
+    });
+    manifest["bin"][0]["name"] = toml_edit::value(&*bin.name);
+    manifest["bin"][0]["path"] = toml_edit::value(format!("src/bin/{}.rs", bin.name));
+    for (key, val) in [
+        ("profile", &template_new.profile),
+        ("dependencies", &template_new.dependencies),
+    ] {
+        if !val.is_empty() {
+            manifest[key] = toml_edit::Item::Table((**val).clone());
+        }
+    }
+
+    crate::fs::create_dir_all(src_path.parent().unwrap())?;
+    crate::fs::write(&manifest_path, manifest.to_string())?;
+    crate::fs::write(&src_path, payload)?;
+
+    for (from, to) in &template_new.copy_files {
+        let from = cargo_compete_config_path.with_file_name("").join(from);
+        crate::fs::copy(from, manifest_dir.join(to))?;
+    }
+
+    let payload_metadata =
+        crate::project::cargo_metadata_no_deps(&manifest_path, &metadata.workspace_root)?;
+    let payload_member = &payload_metadata.packages[0];
+    let payload_bin = payload_member.bin_like_target_by_name(&bin.name)?;
+
+    let mut artifacts = super::build_all(
+        &payload_metadata,
+        payload_member,
+        &[payload_bin],
+        cargo_compete_config.test.toolchain.as_deref(),
+        release,
+        shell,
+    )?;
+    Ok(artifacts.remove(0))
+}
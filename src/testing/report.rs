@@ -0,0 +1,341 @@
+use super::{batch, interactive};
+use crate::shell::Shell;
+use anyhow::bail;
+use az::SaturatingAs as _;
+use serde::{Deserialize, Serialize};
+use snowchains_core::testsuite::ExpectedOutput;
+use std::{fmt::Write as _, path::Path, time::Duration};
+use strum::{EnumString, EnumVariantNames};
+
+/// Format of the report written by `--report-format`.
+#[derive(EnumString, EnumVariantNames, strum::Display, Clone, Copy, Debug, PartialEq)]
+#[strum(serialize_all = "kebab-case")]
+pub enum ReportFormat {
+    Json,
+    Junit,
+}
+
+/// Results of test suites, which is serialized as the JSON report.
+#[derive(Serialize, Debug)]
+pub(crate) struct Report {
+    pub(crate) result: ReportResult,
+    pub(crate) suites: Vec<ReportSuite>,
+}
+
+impl Report {
+    pub(crate) fn new(suites: Vec<ReportSuite>) -> Self {
+        let result = if suites.iter().all(|s| s.result == ReportResult::Passed) {
+            ReportResult::Passed
+        } else {
+            ReportResult::Failed
+        };
+        Self { result, suites }
+    }
+
+    /// Writes this to `path`, or to stdout if `path` is `None`.
+    pub(crate) fn write(
+        &self,
+        format: ReportFormat,
+        path: Option<&Path>,
+        shell: &mut Shell,
+    ) -> anyhow::Result<()> {
+        let content = match format {
+            ReportFormat::Json => serde_json::to_string_pretty(self)? + "\n",
+            ReportFormat::Junit => self.to_junit(),
+        };
+
+        if let Some(path) = path {
+            if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                crate::fs::create_dir_all(parent)?;
+            }
+            crate::fs::write(path, content)?;
+        } else {
+            shell.out().write_all(content.as_ref())?;
+            shell.out().flush()?;
+        }
+        Ok(())
+    }
+
+    fn to_junit(&self) -> String {
+        let num_tests = self.suites.iter().map(|s| s.cases.len()).sum::<usize>();
+        let num_failures = self
+            .suites
+            .iter()
+            .map(ReportSuite::num_failures)
+            .sum::<usize>();
+        let time = self
+            .suites
+            .iter()
+            .map(ReportSuite::elapsed)
+            .sum::<Duration>();
+
+        let mut acc = r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_owned() + "\n";
+        writeln!(
+            acc,
+            r#"<testsuites tests="{}" failures="{}" time="{:.3}">"#,
+            num_tests,
+            num_failures,
+            time.as_secs_f64(),
+        )
+        .unwrap();
+
+        for suite in &self.suites {
+            let classname = format!("{}.{}", suite.package, suite.bin);
+            writeln!(
+                acc,
+                r#"  <testsuite name="{}" tests="{}" failures="{}" time="{:.3}">"#,
+                xml_escape(&classname),
+                suite.cases.len(),
+                suite.num_failures(),
+                suite.elapsed().as_secs_f64(),
+            )
+            .unwrap();
+
+            for case in &suite.cases {
+                write!(
+                    acc,
+                    r#"    <testcase name="{}" classname="{}" time="{:.3}""#,
+                    xml_escape(case.name.as_deref().unwrap_or("")),
+                    xml_escape(&classname),
+                    case.elapsed_ms as f64 / 1000.0,
+                )
+                .unwrap();
+
+                if case.verdict == ReportVerdict::Ac {
+                    acc += "/>\n";
+                    continue;
+                }
+
+                let mut details = "".to_owned();
+                for (header, text) in [("expected", &case.expected), ("actual", &case.actual)] {
+                    if let Some(text) = text {
+                        writeln!(details, "{header}:\n{text}").unwrap();
+                    }
+                }
+
+                writeln!(
+                    acc,
+                    r#">
+      <failure type="{}" message="{}">{}</failure>
+    </testcase>"#,
+                    case.verdict,
+                    xml_escape(&case.summary),
+                    xml_escape(&details),
+                )
+                .unwrap();
+            }
+
+            acc += "  </testsuite>\n";
+        }
+
+        acc += "</testsuites>\n";
+        acc
+    }
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ReportResult {
+    Passed,
+    Failed,
+}
+
+/// Results of a test suite.
+#[derive(Serialize, Debug)]
+pub(crate) struct ReportSuite {
+    pub(crate) package: String,
+    pub(crate) bin: String,
+    pub(crate) alias: String,
+    pub(crate) problem: String,
+    pub(crate) result: ReportResult,
+    pub(crate) cases: Vec<ReportCase>,
+}
+
+impl ReportSuite {
+    pub(crate) fn new(
+        package: &str,
+        bin: &str,
+        alias: &str,
+        problem: &str,
+        cases: Vec<ReportCase>,
+    ) -> Self {
+        let result = if cases.iter().all(|c| c.verdict == ReportVerdict::Ac) {
+            ReportResult::Passed
+        } else {
+            ReportResult::Failed
+        };
+
+        Self {
+            package: package.to_owned(),
+            bin: bin.to_owned(),
+            alias: alias.to_owned(),
+            problem: problem.to_owned(),
+            result,
+            cases,
+        }
+    }
+
+    pub(crate) fn error_on_fail(&self) -> anyhow::Result<()> {
+        let fails = self.num_failures();
+
+        if fails > 0 {
+            bail!(
+                "{}/{} test{} failed",
+                fails,
+                self.cases.len(),
+                if fails == 1 { "" } else { "s" },
+            );
+        }
+        Ok(())
+    }
+
+    pub(crate) fn num_failures(&self) -> usize {
+        self.cases
+            .iter()
+            .filter(|c| c.verdict != ReportVerdict::Ac)
+            .count()
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.cases
+            .iter()
+            .map(|c| Duration::from_millis(c.elapsed_ms))
+            .sum()
+    }
+}
+
+/// Result of a test case.
+#[derive(Serialize, Debug)]
+pub(crate) struct ReportCase {
+    pub(crate) name: Option<String>,
+    pub(crate) verdict: ReportVerdict,
+    pub(crate) summary: String,
+    pub(crate) elapsed_ms: u64,
+    /// In bytes.
+    pub(crate) peak_memory: Option<u64>,
+    pub(crate) expected: Option<String>,
+    pub(crate) actual: Option<String>,
+}
+
+/// Verdict of a test case, which is serialized as `AC`, `WA`, `RE`, `TLE`, `MLE`, or `INVALID`.
+#[derive(Serialize, Deserialize, strum::Display, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "UPPERCASE")]
+#[strum(serialize_all = "UPPERCASE")]
+pub(crate) enum ReportVerdict {
+    Ac,
+    Wa,
+    Re,
+    Tle,
+    Mle,
+    Invalid,
+}
+
+impl ReportCase {
+    pub(crate) fn from_batch(verdict: &batch::Verdict, display_limit: usize) -> Self {
+        let expected = match &verdict.expected {
+            ExpectedOutput::Deterministic(expected) => batch::expected_stdout(expected),
+            ExpectedOutput::Checker { text, .. } => text.as_deref(),
+        };
+
+        Self {
+            name: verdict.test_case_name.clone(),
+            verdict: match verdict.kind {
+                batch::VerdictKind::Accepted => ReportVerdict::Ac,
+                batch::VerdictKind::WrongAnswer => ReportVerdict::Wa,
+                batch::VerdictKind::RuntimeError(_) | batch::VerdictKind::StackOverflow(_) => {
+                    ReportVerdict::Re
+                }
+                batch::VerdictKind::TimelimitExceeded(_) => ReportVerdict::Tle,
+                batch::VerdictKind::MemoryLimitExceeded(_) => ReportVerdict::Mle,
+                batch::VerdictKind::InvalidInput => ReportVerdict::Invalid,
+            },
+            summary: verdict.summary(),
+            elapsed_ms: verdict.elapsed.as_millis().saturating_as(),
+            peak_memory: verdict.peak_memory,
+            expected: expected.map(|s| truncate(s, display_limit)),
+            actual: verdict
+                .stdout
+                .as_deref()
+                .map(|s| truncate(s, display_limit)),
+        }
+    }
+
+    pub(crate) fn from_interactive(verdict: &interactive::Verdict, display_limit: usize) -> Self {
+        Self {
+            name: verdict.test_case_name.clone(),
+            verdict: match verdict.kind {
+                interactive::VerdictKind::Accepted => ReportVerdict::Ac,
+                interactive::VerdictKind::WrongAnswer(_) => ReportVerdict::Wa,
+                interactive::VerdictKind::RuntimeError(_)
+                | interactive::VerdictKind::StackOverflow(_) => ReportVerdict::Re,
+                interactive::VerdictKind::TimelimitExceeded(_) => ReportVerdict::Tle,
+            },
+            summary: verdict.summary(),
+            elapsed_ms: verdict.elapsed.as_millis().saturating_as(),
+            peak_memory: None,
+            expected: None,
+            actual: Some(truncate(&verdict.transcript(), display_limit)),
+        }
+    }
+}
+
+/// Truncates `s` to at most `limit` bytes on a char boundary.
+fn truncate(s: &str, limit: usize) -> String {
+    if s.len() <= limit {
+        return s.to_owned();
+    }
+    let end = (0..=limit)
+        .rev()
+        .find(|&i| s.is_char_boundary(i))
+        .unwrap_or(0);
+    s[..end].to_owned()
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut acc = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => acc += "&amp;",
+            '<' => acc += "&lt;",
+            '>' => acc += "&gt;",
+            '"' => acc += "&quot;",
+            '\'' => acc += "&apos;",
+            // Not allowed in XML 1.0.
+            c if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => acc.push('\u{fffd}'),
+            c => acc.push(c),
+        }
+    }
+    acc
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::report::{truncate, xml_escape, ReportVerdict};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn truncate_on_char_boundary() {
+        assert_eq!("abc", truncate("abc", 3));
+        assert_eq!("ab", truncate("abc", 2));
+        assert_eq!("a", truncate("aあ", 3));
+    }
+
+    #[test]
+    fn serialize_verdicts() -> anyhow::Result<()> {
+        assert_eq!(r#""AC""#, serde_json::to_string(&ReportVerdict::Ac)?);
+        assert_eq!(
+            r#""INVALID""#,
+            serde_json::to_string(&ReportVerdict::Invalid)?
+        );
+        assert_eq!("TLE", ReportVerdict::Tle.to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn xml_escape_special_chars() {
+        assert_eq!(
+            "&lt;a href=&quot;x&quot;&gt;&amp;&apos;\u{fffd}\n",
+            xml_escape("<a href=\"x\">&'\u{1b}\n"),
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use super::batch::display_memory;
+use crate::{config::CargoCompeteConfigTestRun, project::PackageExt as _, shell::Shell};
+use anyhow::{bail, Context as _};
+use cargo_metadata as cm;
+use liquid::object;
+use std::{
+    fs,
+    io::Write as _,
+    path::Path,
+    process::{Command, Stdio},
+    thread,
+    time::Instant,
+};
+use termcolor::Color;
+use url::Url;
+
+pub(crate) struct Args<'a> {
+    pub(crate) metadata: &'a cm::Metadata,
+    pub(crate) member: &'a cm::Package,
+    pub(crate) bin: &'a cm::Target,
+    pub(crate) bin_alias: &'a str,
+    pub(crate) problem_url: &'a Url,
+    pub(crate) toolchain: Option<&'a str>,
+    pub(crate) run: &'a CargoCompeteConfigTestRun,
+    pub(crate) release: bool,
+    pub(crate) input: Input<'a>,
+    pub(crate) cargo_compete_exe: Option<&'a Path>,
+    pub(crate) shell: &'a mut Shell,
+}
+
+/// Where the standard input comes from.
+pub(crate) enum Input<'a> {
+    Inherit,
+    File(&'a Path),
+    /// A newline is appended if it does not end with one.
+    Text(&'a str),
+}
+
+/// Builds `bin` and runs it once in the same way as `test`, letting it write to the terminal.
+///
+/// The exit status, the elapsed time, and the peak memory usage are printed at the end.
+pub(crate) fn run(args: Args<'_>) -> anyhow::Result<()> {
+    let Args {
+        metadata,
+        member,
+        bin,
+        bin_alias,
+        problem_url,
+        toolchain,
+        run,
+        release,
+        input,
+        cargo_compete_exe,
+        shell,
+    } = args;
+
+    let artifact = super::build(metadata, member, bin, toolchain, release, shell)?;
+
+    let vars = object!({
+        "manifest_dir": member.manifest_dir(),
+        "bin_name": &bin.name,
+        "bin_alias": bin_alias,
+    });
+    let cmd = super::command_expression(
+        &artifact,
+        run,
+        &vars,
+        &metadata.workspace_root,
+        problem_url,
+        cargo_compete_exe,
+    )?;
+
+    let stdin = match input {
+        Input::Inherit => Stdio::inherit(),
+        Input::File(path) => fs::File::open(path)
+            .with_context(|| format!("could not open `{}`", path.display()))?
+            .into(),
+        Input::Text(_) => Stdio::piped(),
+    };
+
+    shell.status("Running", format!("`{artifact}`"))?;
+
+    let started = Instant::now();
+
+    let mut child = Command::new(&cmd.program)
+        .args(&cmd.args)
+        .current_dir(&cmd.cwd)
+        .envs(&cmd.env)
+        .stdin(stdin)
+        .spawn()
+        .with_context(|| format!("could not execute `{}`", cmd.program.to_string_lossy()))?;
+
+    if let Input::Text(text) = input {
+        let mut text = text.to_owned();
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+        let mut stdin = child.stdin.take().unwrap();
+        // The program may exit without reading all of the input.
+        thread::spawn(move || stdin.write_all(text.as_ref()));
+    }
+
+    let super::Exited {
+        status,
+        peak_memory,
+    } = super::wait_with_deadline(&mut child, None)?.expect("no deadline");
+    let elapsed = started.elapsed();
+
+    let message = match peak_memory {
+        Some(peak_memory) => format!(
+            "{} ({} ms, {})",
+            status,
+            elapsed.as_millis(),
+            display_memory(peak_memory),
+        ),
+        None => format!("{} ({} ms)", status, elapsed.as_millis()),
+    };
+
+    if status.success() {
+        shell.status("Finished", message)?;
+    } else {
+        shell.status_with_color("Finished", message, Color::Red)?;
+        bail!("`{}` exited unsuccessfully", bin.name);
+    }
+    Ok(())
+}
@@ -0,0 +1,334 @@
+use super::{
+    batch::{JudgeOutcome, VerdictKind},
+    validator::Validator,
+};
+use crate::{
+    config::{CargoCompeteConfigTestChecker, CargoCompeteConfigTestRun},
+    project::PackageExt as _,
+    shell::Shell,
+};
+use anyhow::{anyhow, bail, Context as _};
+use az::SaturatingAs as _;
+use camino::{Utf8Path, Utf8PathBuf};
+use cargo_metadata as cm;
+use human_size::{Byte, Size};
+use indicatif::{ProgressBar, ProgressStyle};
+use liquid::object;
+use snowchains_core::{
+    judge::CommandExpression,
+    testsuite::{Additional, BatchTestSuite, PartialBatchTestCase, TestSuite},
+};
+use std::{
+    collections::HashSet,
+    num::NonZeroUsize,
+    path::Path,
+    time::{Duration, Instant},
+};
+use url::Url;
+
+pub(crate) struct Args<'a> {
+    pub(crate) metadata: &'a cm::Metadata,
+    pub(crate) member: &'a cm::Package,
+    pub(crate) bin: &'a cm::Target,
+    pub(crate) bin_alias: &'a str,
+    pub(crate) generator: &'a cm::Target,
+    pub(crate) reference: &'a cm::Target,
+    pub(crate) cargo_compete_config_test_suite: &'a liquid::Template,
+    pub(crate) problem_url: &'a Url,
+    pub(crate) toolchain: Option<&'a str>,
+    pub(crate) checker: Option<&'a CargoCompeteConfigTestChecker>,
+    pub(crate) run: &'a CargoCompeteConfigTestRun,
+    pub(crate) release: bool,
+    pub(crate) iterations: Option<u64>,
+    pub(crate) duration: Option<Duration>,
+    pub(crate) seed: u64,
+    pub(crate) display_limit: Size,
+    pub(crate) diff: bool,
+    pub(crate) cargo_compete_exe: Option<&'a Path>,
+    pub(crate) shell: &'a mut Shell,
+}
+
+/// Runs `bin` and `reference` against inputs from `generator` until they disagree.
+///
+/// The generator is called as `<generator> <seed>` with a different seed for each iteration. The
+/// first failing input is saved in the test suite directory as `<bin_alias>/in/stress-<seed>.txt`
+/// along with the output of `reference`. The generator and `reference` fail if they exceed the
+/// timelimit of the test suite.
+pub(crate) fn stress(args: Args<'_>) -> anyhow::Result<()> {
+    let Args {
+        metadata,
+        member,
+        bin,
+        bin_alias,
+        generator,
+        reference,
+        cargo_compete_config_test_suite,
+        problem_url,
+        toolchain,
+        checker,
+        run,
+        release,
+        iterations,
+        duration,
+        seed,
+        display_limit,
+        diff,
+        cargo_compete_exe,
+        shell,
+    } = args;
+
+    let test_suite_path = super::test_suite_path(
+        &metadata.workspace_root,
+        member.manifest_dir(),
+        cargo_compete_config_test_suite,
+        &bin.name,
+        bin_alias,
+        problem_url,
+        shell,
+    )?;
+
+    let test_suite_dir = test_suite_path.parent().unwrap();
+
+    let vars = object!({
+        "manifest_dir": member.manifest_dir(),
+        "bin_name": &bin.name,
+        "bin_alias": bin_alias,
+    });
+
+    let mut test_suite = match crate::fs::read_yaml(&test_suite_path)? {
+        TestSuite::Batch(test_suite) => test_suite,
+        TestSuite::Interactive(_) => bail!("`stress` does not support `Interactive` problems"),
+        TestSuite::Unsubmittable => bail!("this is `Unsubmittable` problem"),
+    };
+
+    let super::BatchTestSuiteExtra {
+        checker: checker_in_test_suite,
+        memorylimit,
+        validator,
+    } = crate::fs::read_yaml(&test_suite_path)?;
+
+    if let Some(checker) =
+        super::resolve_checker(checker_in_test_suite, test_suite_dir, checker, &vars, shell)?
+    {
+        test_suite.r#match = checker.to_match(test_suite_dir)?;
+    }
+
+    let memory_limit = super::resolve_memory_limit(memorylimit.as_deref(), &test_suite_path, None)?;
+    let validator = super::resolve_validator(
+        validator,
+        test_suite_dir,
+        metadata,
+        member,
+        bin,
+        toolchain,
+        release,
+        shell,
+    )?;
+
+    let artifacts = super::build_all(
+        metadata,
+        member,
+        &[generator, reference, bin],
+        toolchain,
+        release,
+        shell,
+    )?;
+    let (generator, reference, bin) = (&artifacts[0], &artifacts[1], &artifacts[2]);
+    let cmd = super::command_expression(
+        bin,
+        run,
+        &vars,
+        &metadata.workspace_root,
+        problem_url,
+        cargo_compete_exe,
+    )?;
+
+    // Unless either of them is specified, run 100 times.
+    let iterations = match (iterations, duration) {
+        (None, None) => Some(100),
+        (iterations, _) => iterations,
+    };
+
+    shell.status(
+        "Stressing",
+        format!(
+            "{} against {} (seed = {})",
+            bin.file_name().unwrap_or_default(),
+            reference.file_name().unwrap_or_default(),
+            seed,
+        ),
+    )?;
+
+    let pb = ProgressBar::new_spinner();
+    pb.set_draw_target(shell.progress_draw_target());
+    pb.set_style(ProgressStyle::default_spinner().template("{spinner} {msg:bold}"));
+    pb.enable_steady_tick(50);
+
+    let started = Instant::now();
+    let mut passed = 0;
+
+    let (seed, input, expected, outcome) = loop {
+        if matches!(iterations, Some(n) if passed >= n)
+            || matches!(duration, Some(d) if started.elapsed() >= d)
+        {
+            pb.finish_and_clear();
+            shell.status(
+                "Finished",
+                format!(
+                    "{} iteration{} in {:.1}s. no failing input found",
+                    passed,
+                    if passed == 1 { "" } else { "s" },
+                    started.elapsed().as_secs_f64(),
+                ),
+            )?;
+            return Ok(());
+        }
+
+        let seed = seed.wrapping_add(passed);
+        pb.set_message(&format!("#{} (seed = {})", passed + 1, seed));
+
+        let input = crate::process::process(generator)
+            .arg(seed.to_string())
+            .cwd(&metadata.workspace_root)
+            .timeout(test_suite.timelimit)
+            .read()
+            .with_context(|| format!("the generator failed (seed = {seed})"))?;
+
+        // Inputs violating the constraints make the results meaningless.
+        if let Some(validator) = &validator {
+            if let Some(stderr) = validator.validate(&input)? {
+                pb.finish_and_clear();
+                return Err(anyhow!("{}", stderr.trim_end())).with_context(|| {
+                    format!("the generator produced an invalid input (seed = {seed})")
+                });
+            }
+        }
+
+        let expected = crate::process::process(reference)
+            .pipe_input(Some(&*input))
+            .cwd(&metadata.workspace_root)
+            .timeout(test_suite.timelimit)
+            .read()
+            .with_context(|| format!("the reference solution failed (seed = {seed})"))?;
+
+        let outcome = judge_one(
+            &cmd,
+            &test_suite,
+            test_suite_dir,
+            memory_limit,
+            None,
+            &format!("stress-{seed}"),
+            &input,
+            Some(&expected),
+        )?;
+
+        if outcome
+            .verdicts
+            .iter()
+            .any(|v| v.kind != VerdictKind::Accepted)
+        {
+            break (seed, input, expected, outcome);
+        }
+        passed += 1;
+    };
+
+    pb.finish_and_clear();
+
+    let (in_path, out_path) = text_file_paths(
+        &test_suite_path,
+        &test_suite,
+        bin_alias,
+        &format!("stress-{seed}"),
+        shell,
+    )?;
+    crate::fs::write(&in_path, input)?;
+    crate::fs::write(&out_path, expected)?;
+
+    let display_limit = display_limit.into::<Byte>().value().saturating_as();
+
+    writeln!(shell.err())?;
+    outcome.print_pretty(shell.err(), Some(display_limit), diff)?;
+    writeln!(shell.err())?;
+
+    shell.status("Saved", format!("{in_path} and {out_path}"))?;
+
+    bail!(
+        "found a failing input after {} iteration{} (seed = {})",
+        passed + 1,
+        if passed == 0 { "" } else { "s" },
+        seed,
+    );
+}
+
+/// Judges `cmd` with a single test case in the same manner as the `test` command.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn judge_one(
+    cmd: &CommandExpression,
+    test_suite: &BatchTestSuite,
+    test_suite_dir: &Utf8Path,
+    memory_limit: Option<u64>,
+    validator: Option<&Validator>,
+    name: &str,
+    input: &str,
+    expected: Option<&str>,
+) -> anyhow::Result<JudgeOutcome> {
+    let test_cases = BatchTestSuite {
+        timelimit: test_suite.timelimit,
+        r#match: test_suite.r#match.clone(),
+        cases: vec![PartialBatchTestCase {
+            name: Some(name.to_owned()),
+            r#in: input.into(),
+            out: expected.map(Into::into),
+            timelimit: None,
+            r#match: None,
+        }],
+        extend: vec![],
+    }
+    .load_test_cases(test_suite_dir.as_ref(), None::<HashSet<String>>, |_| {
+        unreachable!("`extend` is empty")
+    })?;
+
+    super::batch::judge(
+        indicatif::ProgressDrawTarget::hidden(),
+        cmd,
+        &test_cases,
+        memory_limit,
+        validator,
+        NonZeroUsize::new(1).unwrap(),
+        false,
+    )
+}
+
+/// Returns paths for a new test case in the directory that `save_test_cases` creates.
+pub(super) fn text_file_paths(
+    test_suite_path: &Utf8Path,
+    test_suite: &BatchTestSuite,
+    bin_alias: &str,
+    stem: &str,
+    shell: &mut Shell,
+) -> anyhow::Result<(Utf8PathBuf, Utf8PathBuf)> {
+    let dir = test_suite_path.with_file_name(bin_alias);
+
+    // The same as what `save_test_cases` writes.
+    let is_loaded = test_suite.extend.iter().any(|extend| {
+        matches!(
+            extend,
+            Additional::Text { path, r#in, out, .. }
+            if *path == format!("./{bin_alias}") && r#in == "/in/*.txt" && out == "/out/*.txt"
+        )
+    });
+    if !is_loaded {
+        shell.warn(format!(
+            "the input is saved in `{dir}`, but the test suite does not load test cases from \
+             there",
+        ))?;
+    }
+
+    crate::fs::create_dir_all(dir.join("in"))?;
+    crate::fs::create_dir_all(dir.join("out"))?;
+    let file_name = format!("{stem}.txt");
+    Ok((
+        dir.join("in").join(&file_name),
+        dir.join("out").join(file_name),
+    ))
+}
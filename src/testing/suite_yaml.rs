@@ -0,0 +1,187 @@
+use anyhow::{bail, ensure, Context as _};
+use std::iter;
+use yaml_rust::{
+    parser::{Event, Parser},
+    scanner::Marker,
+};
+
+/// Positions of `cases`.
+pub(super) struct CasesSpan {
+    /// The start of the sequence.
+    pub(super) start: Marker,
+    /// The end of the sequence.
+    pub(super) end: Marker,
+    pub(super) cases: Vec<CaseSpan>,
+}
+
+/// Positions of a test case in `cases`.
+pub(super) struct CaseSpan {
+    /// The start of the mapping.
+    pub(super) start: Marker,
+    /// The value of `name`.
+    pub(super) name: Option<Marker>,
+    pub(super) in_key: Marker,
+    /// The key, and the next event of the value.
+    pub(super) out: Option<(Marker, Marker)>,
+    /// The end of the mapping.
+    pub(super) end: Marker,
+}
+
+/// Byte offsets of the characters, since `Marker`s count characters.
+pub(super) struct Offsets(Vec<usize>);
+
+impl Offsets {
+    pub(super) fn new(yaml: &str) -> Self {
+        Self(
+            yaml.char_indices()
+                .map(|(i, _)| i)
+                .chain(iter::once(yaml.len()))
+                .collect(),
+        )
+    }
+
+    pub(super) fn of(&self, marker: &Marker) -> usize {
+        self.0[marker.index()]
+    }
+
+    pub(super) fn line_start(&self, marker: &Marker) -> usize {
+        self.0[marker.index() - marker.col()]
+    }
+}
+
+pub(super) fn cases_span(yaml: &str) -> anyhow::Result<CasesSpan> {
+    let events = parse(yaml)?;
+
+    ensure!(
+        matches!(
+            *events,
+            [
+                (Event::StreamStart, _),
+                (Event::DocumentStart, _),
+                (Event::MappingStart(_), _),
+                ..
+            ]
+        ),
+        "expected a mapping",
+    );
+
+    let mut i = 3;
+    while !matches!(events.get(i), Some((Event::MappingEnd, _)) | None) {
+        let is_cases = matches!(&events[i].0, Event::Scalar(k, ..) if k == "cases");
+        let value = i + 1;
+        i = skip_node(&events, value)?;
+
+        if !is_cases {
+            continue;
+        }
+        ensure!(
+            matches!(events.get(value), Some((Event::SequenceStart(_), _))),
+            "expected `cases` to be a sequence",
+        );
+
+        let mut cases = vec![];
+
+        let mut j = value + 1;
+        while !matches!(events.get(j), Some((Event::SequenceEnd, _)) | None) {
+            ensure!(
+                matches!(events[j].0, Event::MappingStart(_)),
+                "expected `cases[{}]` to be a mapping",
+                cases.len(),
+            );
+
+            let (mut name, mut in_key, mut out) = (None, None, None);
+
+            let mut k = j + 1;
+            while !matches!(events.get(k), Some((Event::MappingEnd, _)) | None) {
+                let next = skip_node(&events, k + 1)?;
+                match &events[k].0 {
+                    Event::Scalar(key, ..) if key == "name" => name = Some(events[k + 1].1),
+                    Event::Scalar(key, ..) if key == "in" => in_key = Some(events[k].1),
+                    Event::Scalar(key, ..) if key == "out" => {
+                        out = Some((events[k].1, events[next].1));
+                    }
+                    _ => {}
+                }
+                k = next;
+            }
+
+            cases.push(CaseSpan {
+                start: events[j].1,
+                name,
+                in_key: in_key.with_context(|| format!("`cases[{}].in` not found", cases.len()))?,
+                out,
+                end: events.get(k).with_context(|| "unexpected end")?.1,
+            });
+            j = k + 1;
+        }
+
+        return Ok(CasesSpan {
+            start: events[value].1,
+            end: events.get(j).with_context(|| "unexpected end")?.1,
+            cases,
+        });
+    }
+
+    bail!("`cases` not found");
+}
+
+fn parse(yaml: &str) -> anyhow::Result<Vec<(Event, Marker)>> {
+    let mut parser = Parser::new(yaml.chars());
+    let mut acc = vec![];
+    loop {
+        let (event, marker) = parser.next()?;
+        if event == Event::StreamEnd {
+            break Ok(acc);
+        }
+        acc.push((event, marker));
+    }
+}
+
+/// Returns the index next to the node that starts at `i`.
+fn skip_node(events: &[(Event, Marker)], i: usize) -> anyhow::Result<usize> {
+    let mut depth = 0;
+    for (j, (event, _)) in events.iter().enumerate().skip(i) {
+        match event {
+            Event::SequenceStart(_) | Event::MappingStart(_) => depth += 1,
+            Event::SequenceEnd | Event::MappingEnd => depth -= 1,
+            _ => {}
+        }
+        if depth == 0 {
+            return Ok(j + 1);
+        }
+    }
+    bail!("unexpected end");
+}
+
+pub(super) fn skip_back_blank_lines(yaml: &str, mut pos: usize) -> usize {
+    while let Some(prev) = yaml[..pos].strip_suffix('\n') {
+        let prev_line_start = prev.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        if !prev[prev_line_start..].trim().is_empty() {
+            break;
+        }
+        pos = prev_line_start;
+    }
+    pos
+}
+
+/// Formats `s` as a literal block scalar if possible, or as a double-quoted scalar.
+pub(super) fn to_yaml_value(s: &str, indent: usize) -> String {
+    let is_plain_text = s.ends_with('\n')
+        && !s.ends_with("\n\n")
+        && !s.starts_with(&[' ', '\n'][..])
+        && s.chars().all(|c| c == '\n' || !c.is_control());
+
+    if is_plain_text {
+        let mut acc = "|\n".to_owned();
+        for line in s.lines() {
+            if !line.is_empty() {
+                acc += &" ".repeat(indent + 2);
+                acc += line;
+            }
+            acc += "\n";
+        }
+        acc
+    } else {
+        serde_json::to_string(s).unwrap() + "\n"
+    }
+}
@@ -0,0 +1,427 @@
+use super::{
+    batch::expected_stdout,
+    suite_yaml::{self, skip_back_blank_lines, to_yaml_value, Offsets},
+};
+use crate::shell::Shell;
+use anyhow::{anyhow, bail, ensure, Context as _};
+use camino::Utf8Path;
+use cargo_metadata as cm;
+use prettytable::{row, Row};
+use snowchains_core::testsuite::{
+    Additional, BatchTestSuite, ExpectedOutput, PartialBatchTestCase, TestSuite,
+};
+use std::{collections::HashSet, ops::Range};
+
+pub(crate) struct AddArgs<'a> {
+    pub(crate) metadata: &'a cm::Metadata,
+    pub(crate) member: &'a cm::Package,
+    /// The `bin`/`example` that the test suite is for.
+    pub(crate) bin: &'a cm::Target,
+    pub(crate) test_suite_path: &'a Utf8Path,
+    /// Defaults to `handmade<N>`.
+    pub(crate) name: Option<&'a str>,
+    pub(crate) input: &'a str,
+    pub(crate) output: Output<'a>,
+    pub(crate) toolchain: Option<&'a str>,
+    pub(crate) release: bool,
+    pub(crate) shell: &'a mut Shell,
+}
+
+/// Where the expected output of a new test case comes from.
+pub(crate) enum Output<'a> {
+    None,
+    Text(&'a str),
+    /// The output of the `bin`/`example`.
+    Reference(&'a cm::Target),
+}
+
+/// Appends a test case to `cases` of the test suite.
+///
+/// The input is checked with the validator first if the test suite or `bin` has one.
+pub(crate) fn add(args: AddArgs<'_>) -> anyhow::Result<()> {
+    let AddArgs {
+        metadata,
+        member,
+        bin,
+        test_suite_path,
+        name,
+        input,
+        output,
+        toolchain,
+        release,
+        shell,
+    } = args;
+
+    let (yaml, test_suite) = read(test_suite_path)?;
+
+    let names = names(&test_suite, test_suite_path)?;
+    let name = match name {
+        Some(name) => {
+            ensure!(!names.contains(name), "{:?} already exists", name);
+            name.to_owned()
+        }
+        None => (1..)
+            .map(|i| format!("handmade{i}"))
+            .find(|name| !names.contains(&**name))
+            .unwrap(),
+    };
+
+    let super::BatchTestSuiteExtra { validator, .. } = serde_yaml::from_str(&yaml)
+        .with_context(|| format!("could not parse the YAML file at `{test_suite_path}`"))?;
+    let validator = super::resolve_validator(
+        validator,
+        test_suite_path.parent().unwrap(),
+        metadata,
+        member,
+        bin,
+        toolchain,
+        release,
+        shell,
+    )?;
+    if let Some(validator) = validator {
+        if let Some(stderr) = validator.validate(input)? {
+            return Err(anyhow!("{}", stderr.trim_end()))
+                .with_context(|| "the input is rejected by the validator");
+        }
+    }
+
+    let output = match output {
+        Output::None => None,
+        Output::Text(text) => Some(text.to_owned()),
+        Output::Reference(reference) => {
+            let reference = super::build(metadata, member, reference, toolchain, release, shell)?;
+            let output = crate::process::process(&reference)
+                .pipe_input(Some(input))
+                .cwd(&metadata.workspace_root)
+                .read()
+                .with_context(|| "the reference solution failed")?;
+            Some(output)
+        }
+    };
+
+    let case = PartialBatchTestCase {
+        name: Some(name.clone()),
+        r#in: input.into(),
+        out: output.map(Into::into),
+        timelimit: None,
+        r#match: None,
+    };
+
+    let edited = insert_case(&yaml, &case).and_then(|edited| {
+        let mut expected = test_suite;
+        expected.cases.push(case);
+        check(&edited, &expected)?;
+        Ok(edited)
+    });
+    let edited = edited.with_context(|| format!("could not edit `{test_suite_path}`"))?;
+    crate::fs::write(test_suite_path, edited)?;
+
+    shell.status("Added", format!("{name:?} to `{test_suite_path}`"))?;
+    Ok(())
+}
+
+/// Prints the test cases in `cases` and the text files in `extend`.
+///
+/// System test cases are not downloaded.
+pub(crate) fn list(test_suite_path: &Utf8Path, shell: &mut Shell) -> anyhow::Result<()> {
+    let (_, test_suite) = read(test_suite_path)?;
+    let num_cases = test_suite.cases.len();
+
+    let test_cases = test_suite.load_test_cases(
+        test_suite_path.parent().unwrap().as_ref(),
+        None::<HashSet<String>>,
+        |_| Ok(vec![]),
+    )?;
+
+    let mut rows = vec![row!["Name", "In", "Out", "Source"]];
+
+    for (i, test_case) in test_cases.iter().enumerate() {
+        let output = match &test_case.output {
+            ExpectedOutput::Deterministic(expected) => expected_stdout(expected),
+            ExpectedOutput::Checker { text, .. } => text.as_deref(),
+        };
+        rows.push(Row::from(vec![
+            format!("{:?}", test_case.name.as_deref().unwrap_or("")),
+            format!("{} B", test_case.input.len()),
+            output.map_or_else(|| "-".to_owned(), |s| format!("{} B", s.len())),
+            if i < num_cases { "cases" } else { "extend" }.to_owned(),
+        ]));
+    }
+
+    if test_suite
+        .extend
+        .iter()
+        .any(|e| matches!(e, Additional::SystemTestCases { .. }))
+    {
+        rows.push(row!["(system test cases)", "", "", "extend"]);
+    }
+
+    shell.print_table(&rows)?;
+    Ok(())
+}
+
+/// Removes a test case from `cases` of the test suite.
+pub(crate) fn remove(
+    test_suite_path: &Utf8Path,
+    name: &str,
+    shell: &mut Shell,
+) -> anyhow::Result<()> {
+    let (yaml, mut test_suite) = read(test_suite_path)?;
+    let i = position(&test_suite, name)?;
+
+    let edited = remove_case(&yaml, i).and_then(|edited| {
+        test_suite.cases.remove(i);
+        check(&edited, &test_suite)?;
+        Ok(edited)
+    });
+    let edited = edited.with_context(|| format!("could not edit `{test_suite_path}`"))?;
+    crate::fs::write(test_suite_path, edited)?;
+
+    shell.status("Removed", format!("{name:?} from `{test_suite_path}`"))?;
+    Ok(())
+}
+
+/// Renames a test case in `cases` of the test suite.
+pub(crate) fn rename(
+    test_suite_path: &Utf8Path,
+    from: &str,
+    to: &str,
+    shell: &mut Shell,
+) -> anyhow::Result<()> {
+    let (yaml, mut test_suite) = read(test_suite_path)?;
+    let i = position(&test_suite, from)?;
+    ensure!(
+        !names(&test_suite, test_suite_path)?.contains(to),
+        "{:?} already exists",
+        to,
+    );
+
+    let edited = rename_case(&yaml, i, to).and_then(|edited| {
+        test_suite.cases[i].name = Some(to.to_owned());
+        check(&edited, &test_suite)?;
+        Ok(edited)
+    });
+    let edited = edited.with_context(|| format!("could not edit `{test_suite_path}`"))?;
+    crate::fs::write(test_suite_path, edited)?;
+
+    shell.status("Renamed", format!("{from:?} to {to:?}"))?;
+    Ok(())
+}
+
+fn read(test_suite_path: &Utf8Path) -> anyhow::Result<(String, BatchTestSuite)> {
+    let yaml = crate::fs::read_to_string(test_suite_path)?;
+    match crate::fs::read_yaml(test_suite_path)? {
+        TestSuite::Batch(test_suite) => Ok((yaml, test_suite)),
+        TestSuite::Interactive(_) => bail!("`Interactive` problems are not supported"),
+        TestSuite::Unsubmittable => bail!("this is `Unsubmittable` problem"),
+    }
+}
+
+/// Returns the names in `cases` and the text files in `extend`.
+fn names(
+    test_suite: &BatchTestSuite,
+    test_suite_path: &Utf8Path,
+) -> anyhow::Result<HashSet<String>> {
+    let test_cases = test_suite.load_test_cases(
+        test_suite_path.parent().unwrap().as_ref(),
+        None::<HashSet<String>>,
+        |_| Ok(vec![]),
+    )?;
+    Ok(test_cases.into_iter().flat_map(|c| c.name).collect())
+}
+
+fn position(test_suite: &BatchTestSuite, name: &str) -> anyhow::Result<usize> {
+    test_suite
+        .cases
+        .iter()
+        .position(|c| c.name.as_deref() == Some(name))
+        .with_context(|| format!("no test case named {name:?} in `cases`"))
+}
+
+/// Checks the edited test suite by parsing it again.
+fn check(edited: &str, expected: &BatchTestSuite) -> anyhow::Result<()> {
+    let ok = matches!(
+        serde_yaml::from_str(edited),
+        Ok(TestSuite::Batch(edited)) if edited == *expected
+    );
+    ensure!(ok, "the edited test suite is broken");
+    Ok(())
+}
+
+fn insert_case(yaml: &str, case: &PartialBatchTestCase) -> anyhow::Result<String> {
+    let span = suite_yaml::cases_span(yaml)?;
+    let offsets = Offsets::new(yaml);
+
+    let is_flow = yaml[offsets.of(&span.start)..].starts_with('[');
+
+    let indent = if is_flow { 2 } else { span.start.col() };
+    let mut item = format!(
+        "{:indent$}- name: {}\n",
+        "",
+        yaml_string(case.name.as_deref().unwrap_or_default())?,
+        indent = indent,
+    );
+    item += &format!(
+        "{:indent$}in: {}",
+        "",
+        to_yaml_value(&case.r#in, indent + 2),
+        indent = indent + 2,
+    );
+    if let Some(out) = &case.out {
+        item += &format!(
+            "{:indent$}out: {}",
+            "",
+            to_yaml_value(out, indent + 2),
+            indent = indent + 2,
+        );
+    }
+
+    let (range, text) = if is_flow {
+        // `cases: []`
+        ensure!(span.cases.is_empty(), "`cases` is not in block style");
+        let start = yaml[..offsets.of(&span.start)].trim_end_matches(' ').len();
+        let range = start..offsets.of(&span.end) + 1;
+        (range, format!("\n{}", item.trim_end_matches('\n')))
+    } else {
+        let pos = skip_back_blank_lines(yaml, offsets.line_start(&span.end));
+        let prefix = if yaml[..pos].ends_with('\n') {
+            ""
+        } else {
+            "\n"
+        };
+        (pos..pos, format!("{prefix}{item}"))
+    };
+
+    let mut edited = yaml.to_owned();
+    edited.replace_range(range, &text);
+    Ok(edited)
+}
+
+fn remove_case(yaml: &str, i: usize) -> anyhow::Result<String> {
+    let span = suite_yaml::cases_span(yaml)?;
+    let offsets = Offsets::new(yaml);
+
+    ensure!(
+        !yaml[offsets.of(&span.start)..].starts_with('['),
+        "`cases` is not in block style",
+    );
+
+    let end = match span.cases.get(i + 1) {
+        Some(next) => offsets.line_start(&next.start),
+        None => skip_back_blank_lines(yaml, offsets.line_start(&span.end)),
+    };
+
+    let (Range { start, end }, text) = if span.cases.len() == 1 {
+        (offsets.of(&span.start)..end, "[]\n")
+    } else {
+        (offsets.line_start(&span.cases[i].start)..end, "")
+    };
+
+    let mut edited = yaml.to_owned();
+    edited.replace_range(start..end, text);
+    Ok(edited)
+}
+
+fn rename_case(yaml: &str, i: usize, name: &str) -> anyhow::Result<String> {
+    let span = suite_yaml::cases_span(yaml)?;
+    let offsets = Offsets::new(yaml);
+
+    let value = span.cases[i]
+        .name
+        .with_context(|| format!("`cases[{i}].name` not found"))?;
+    let start = offsets.of(&value);
+    let end = start + yaml[start..].find('\n').unwrap_or(yaml.len() - start);
+    let end = start + yaml[start..end].trim_end().len();
+
+    let mut edited = yaml.to_owned();
+    edited.replace_range(start..end, &yaml_string(name)?);
+    Ok(edited)
+}
+
+/// Formats `s` as a plain scalar if possible, or as a quoted scalar.
+fn yaml_string(s: &str) -> anyhow::Result<String> {
+    let yaml = serde_yaml::to_string(s)?;
+    Ok(yaml.trim_start_matches("---\n").trim_end().to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::testcase::{insert_case, remove_case, rename_case};
+    use pretty_assertions::assert_eq;
+    use snowchains_core::testsuite::PartialBatchTestCase;
+
+    const YAML: &str = r#"---
+type: Batch
+timelimit: 2s
+match: Lines
+
+cases:
+  # samples
+  - name: sample1
+    in: |
+      1 2
+    out: |
+      3
+  - name: sample2
+    in: |
+      3 4
+    out: |
+      7
+
+extend:
+  - type: Text
+    path: "./a"
+    in: /in/*.txt
+    out: /out/*.txt
+"#;
+
+    #[test]
+    fn insert_case_keeps_formatting() -> anyhow::Result<()> {
+        let case = PartialBatchTestCase {
+            name: Some("handmade1".to_owned()),
+            r#in: "5 6\n".into(),
+            out: Some("11\n".into()),
+            timelimit: None,
+            r#match: None,
+        };
+
+        assert_eq!(
+            YAML.replace(
+                "      7\n",
+                "      7\n  - name: handmade1\n    in: |\n      5 6\n    out: |\n      11\n",
+            ),
+            insert_case(YAML, &case)?,
+        );
+
+        let yaml = "---\ntype: Batch\nmatch: Lines\ncases: []\nextend: []\n";
+        assert_eq!(
+            "---\ntype: Batch\nmatch: Lines\ncases:\n  - name: handmade1\n    in: |\n      5 6\n    \
+             out: |\n      11\nextend: []\n",
+            insert_case(yaml, &case)?,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn remove_and_rename_case() -> anyhow::Result<()> {
+        assert_eq!(
+            YAML.replace(
+                "  - name: sample1\n    in: |\n      1 2\n    out: |\n      3\n",
+                ""
+            ),
+            remove_case(YAML, 0)?,
+        );
+        assert_eq!(
+            YAML.replace(
+                "  - name: sample2\n    in: |\n      3 4\n    out: |\n      7\n",
+                ""
+            ),
+            remove_case(YAML, 1)?,
+        );
+        assert_eq!(
+            YAML.replace("name: sample2", "name: \"123\""),
+            rename_case(YAML, 1, "123")?,
+        );
+        Ok(())
+    }
+}
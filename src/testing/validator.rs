@@ -0,0 +1,70 @@
+use anyhow::Context as _;
+use camino::Utf8PathBuf;
+use serde::Deserialize;
+use std::{
+    io::Write as _,
+    path::PathBuf,
+    process::{Command, Output, Stdio},
+    thread,
+};
+
+/// `validator` in the test suite.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub(super) enum ValidatorSpec {
+    /// A command run in the directory of the test suite.
+    Cmd(Vec<String>),
+    /// Name or alias for a `bin`/`example` in the package.
+    Bin(String),
+}
+
+/// A program that checks whether an input satisfies the constraints.
+///
+/// The input is given to the standard input, and a non-zero exit status means the input is
+/// invalid. Validators for testlib can be used as they are.
+#[derive(Debug)]
+pub(crate) struct Validator {
+    pub(super) program: PathBuf,
+    pub(super) args: Vec<String>,
+    pub(super) cwd: Utf8PathBuf,
+}
+
+impl Validator {
+    /// Returns the standard error of the validator if `input` is invalid.
+    pub(crate) fn validate(&self, input: &str) -> anyhow::Result<Option<String>> {
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .current_dir(&self.cwd)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("could not execute `{}`", self.program.display()))?;
+
+        let mut stdin = child.stdin.take().unwrap();
+        let input = input.to_owned();
+        // The validator may exit without reading all of the input.
+        thread::spawn(move || stdin.write_all(input.as_ref()));
+
+        let Output { status, stderr, .. } = child.wait_with_output()?;
+
+        Ok((!status.success()).then(|| String::from_utf8_lossy(&stderr).into_owned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::validator::ValidatorSpec;
+
+    #[test]
+    fn deserialize_validator_spec() -> anyhow::Result<()> {
+        let spec = serde_yaml::from_str::<ValidatorSpec>("cmd: [\"./validate\", \"--strict\"]\n")?;
+        assert!(matches!(spec, ValidatorSpec::Cmd(cmd) if cmd == ["./validate", "--strict"]));
+
+        let spec = serde_yaml::from_str::<ValidatorSpec>("bin: validate\n")?;
+        assert!(matches!(spec, ValidatorSpec::Bin(bin) if bin == "validate"));
+
+        assert!(serde_yaml::from_str::<ValidatorSpec>("path: ./validate\n").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+use crate::project::PackageExt as _;
+use camino::Utf8PathBuf;
+use cargo_metadata as cm;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+const INTERVAL: Duration = Duration::from_millis(300);
+
+/// Polls modification times of files.
+///
+/// Polling is used instead of file system events so that it works the same way on every
+/// platform, including network drives and WSL.
+pub(crate) struct Watcher {
+    paths: Vec<Utf8PathBuf>,
+    last: BTreeMap<PathBuf, (SystemTime, u64)>,
+}
+
+impl Watcher {
+    /// Watches `paths`. Directories are watched recursively, except for `target` and hidden ones.
+    pub(crate) fn new(paths: Vec<Utf8PathBuf>) -> Self {
+        let last = snapshot(&paths);
+        Self { paths, last }
+    }
+
+    /// Blocks until any of the files is created, modified, or removed.
+    ///
+    /// Returns `Err` on Ctrl-C, which no longer terminates the process once a test has run.
+    pub(crate) fn wait(&mut self) -> anyhow::Result<()> {
+        loop {
+            thread::sleep(INTERVAL);
+            super::check_ctrl_c()?;
+            if snapshot(&self.paths) != self.last {
+                // Editors may write a file in several steps.
+                thread::sleep(INTERVAL);
+                self.last = snapshot(&self.paths);
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn snapshot(paths: &[Utf8PathBuf]) -> BTreeMap<PathBuf, (SystemTime, u64)> {
+    let mut acc = BTreeMap::new();
+    for path in paths {
+        visit(path.as_ref(), &mut acc);
+    }
+    return acc;
+
+    fn visit(path: &Path, acc: &mut BTreeMap<PathBuf, (SystemTime, u64)>) {
+        let metadata = match fs::metadata(path) {
+            Ok(metadata) => metadata,
+            Err(_) => return,
+        };
+
+        if metadata.is_dir() {
+            let entries = match fs::read_dir(path) {
+                Ok(entries) => entries,
+                Err(_) => return,
+            };
+            for entry in entries.flatten() {
+                let file_name = entry.file_name();
+                let file_name = file_name.to_string_lossy();
+                if !(file_name == "target" || file_name.starts_with('.')) {
+                    visit(&entry.path(), acc);
+                }
+            }
+        } else if let Ok(modified) = metadata.modified() {
+            acc.insert(path.to_owned(), (modified, metadata.len()));
+        }
+    }
+}
+
+/// Returns paths that affect the build of `bin`.
+///
+/// They are `Cargo.toml` of `member`, the source file of `bin` (or its directory if it is
+/// `main.rs`), and the local path dependencies of `member`.
+pub(crate) fn source_paths(
+    metadata: &cm::Metadata,
+    member: &cm::Package,
+    bin: &cm::Target,
+) -> Vec<Utf8PathBuf> {
+    let mut acc = vec![member.manifest_path.clone()];
+
+    acc.push(if bin.src_path.file_name() == Some("main.rs") {
+        bin.src_path.parent().unwrap().to_owned()
+    } else {
+        bin.src_path.clone()
+    });
+
+    let mut visited = BTreeSet::new();
+    let mut stack = vec![member];
+    while let Some(package) = stack.pop() {
+        for path in package.dependencies.iter().flat_map(|d| &d.path) {
+            if visited.insert(path.clone()) {
+                acc.push(path.clone());
+                stack.extend(
+                    metadata
+                        .packages
+                        .iter()
+                        .filter(|p| p.manifest_dir() == path),
+                );
+            }
+        }
+    }
+
+    acc
+}
@@ -0,0 +1,384 @@
+use crate::shell::Shell;
+use anyhow::{anyhow, bail, ensure, Context as _};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use once_cell::sync::Lazy;
+use prettytable::row;
+use regex::Regex;
+use serde::{de::IgnoredAny, Deserialize};
+use sha2::{Digest as _, Sha512};
+use std::{
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use url::Url;
+
+/// Interval between the requests. The API allows one request per 2 seconds.
+const INTERVAL: Duration = Duration::from_secs(2);
+/// Number of the recent submissions shown.
+const COUNT: usize = 10;
+/// How long to wait for a new submission to be listed in `contest.status`.
+const APPEARANCE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Watches the recent submissions of `handle` in the contest until all of them are judged.
+///
+/// If `submission_id` is `Some`, only the submission is watched. Fails if the submission or the
+/// latest one is not accepted.
+pub(crate) fn watch_submissions(
+    api_key: &str,
+    api_secret: &str,
+    handle: &str,
+    contest_id: u64,
+    submission_id: Option<u64>,
+    shell: &mut Shell,
+) -> anyhow::Result<()> {
+    let api = Api::new(api_key, api_secret)?;
+
+    let retrieve = || -> anyhow::Result<_> {
+        let mut submissions = api.contest_status(contest_id, handle, COUNT)?;
+        if let Some(submission_id) = submission_id {
+            submissions.retain(|s| s.id == submission_id);
+        }
+        Ok(submissions)
+    };
+
+    let mut submissions = retrieve()?;
+
+    if let Some(submission_id) = submission_id {
+        // A submission that has just been made may not be listed yet.
+        let deadline = Instant::now() + APPEARANCE_TIMEOUT;
+        while submissions.is_empty() {
+            ensure!(
+                Instant::now() < deadline,
+                "the submission {} did not appear in {} seconds",
+                submission_id,
+                APPEARANCE_TIMEOUT.as_secs(),
+            );
+            thread::sleep(INTERVAL);
+            submissions = retrieve()?;
+        }
+    }
+
+    if submissions.iter().any(Submission::is_in_progress) {
+        let mp = MultiProgress::with_draw_target(shell.progress_draw_target());
+
+        // Newer submissions may be listed later, so the bars are matched by the IDs.
+        let pbs = submissions
+            .iter()
+            .map(|submission| {
+                let pb = mp.add(ProgressBar::new_spinner());
+                pb.set_prefix(&format!(
+                    "{} {}. {} ",
+                    submission.id, submission.problem.index, submission.problem.name,
+                ));
+                update(&pb, submission);
+                (submission.id, pb)
+            })
+            .collect::<Vec<_>>();
+        let finish_and_clear = || pbs.iter().for_each(|(_, pb)| pb.finish_and_clear());
+
+        submissions = thread::scope(|scope| -> anyhow::Result<_> {
+            let handle = scope.spawn(|| loop {
+                thread::sleep(INTERVAL);
+
+                let submissions = match retrieve() {
+                    Ok(submissions) => submissions,
+                    Err(err) => {
+                        finish_and_clear();
+                        break Err(err);
+                    }
+                };
+                for submission in &submissions {
+                    if let Some((_, pb)) = pbs.iter().find(|(id, _)| *id == submission.id) {
+                        update(pb, submission);
+                    }
+                }
+                if !submissions.iter().any(Submission::is_in_progress) {
+                    finish_and_clear();
+                    break Ok(submissions);
+                }
+            });
+            mp.join()?;
+            handle.join().unwrap()
+        })?;
+    }
+
+    let latest = match submissions.first() {
+        Some(latest) => latest,
+        None => {
+            shell.warn(format!("no submissions found in contest {contest_id}"))?;
+            return Ok(());
+        }
+    };
+
+    let mut rows = vec![row![
+        "ID", "Time", "Problem", "Language", "Verdict", "Time", "Memory"
+    ]];
+    for submission in &submissions {
+        rows.push(row![
+            submission.id,
+            humantime::format_rfc3339_seconds(
+                UNIX_EPOCH + Duration::from_secs(submission.creation_time_seconds),
+            ),
+            format!("{}. {}", submission.problem.index, submission.problem.name),
+            submission.programming_language,
+            submission.verdict(),
+            format!("{} ms", submission.time_consumed_millis),
+            format!("{} KiB", submission.memory_consumed_bytes / 1024),
+        ]);
+    }
+    shell.print_table(&rows)?;
+
+    if latest.verdict.as_deref() != Some("OK") {
+        bail!(
+            "the submission {} was not accepted: {}",
+            latest.id,
+            latest.verdict(),
+        );
+    }
+    Ok(())
+}
+
+/// Checks that `handle` is a handle of an existing user.
+pub(crate) fn check_handle(api_key: &str, api_secret: &str, handle: &str) -> anyhow::Result<()> {
+    ensure!(is_valid_handle(handle), "invalid handle: {:?}", handle);
+    Api::new(api_key, api_secret)?.user_info(handle)?;
+    // Otherwise the next call may exceed the limit.
+    thread::sleep(INTERVAL);
+    Ok(())
+}
+
+/// Handles are 3 to 24 characters of Latin letters, digits, `_`, `-`, and `.`.
+fn is_valid_handle(handle: &str) -> bool {
+    static HANDLE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\A[a-zA-Z0-9_.\-]{3,24}\z").unwrap());
+    HANDLE.is_match(handle)
+}
+
+/// Returns the contest ID and the submission ID in the URL of a submission.
+pub(crate) fn parse_submission_url(url: &Url) -> anyhow::Result<(u64, u64)> {
+    (|| {
+        let segments = url.path_segments()?.collect::<Vec<_>>();
+        match *segments {
+            ["contest", contest_id, "submission", submission_id] => {
+                Some((contest_id.parse().ok()?, submission_id.parse().ok()?))
+            }
+            _ => None,
+        }
+    })()
+    .with_context(|| format!("could not parse the submission URL: {url}"))
+}
+
+fn update(pb: &ProgressBar, submission: &Submission) {
+    pb.set_style(
+        ProgressStyle::default_spinner()
+            .template(&format!("{{prefix}}{{msg:{}}}", submission.style())),
+    );
+    pb.set_message(&submission.verdict());
+}
+
+/// An element of the result of `contest.status`.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Submission {
+    id: u64,
+    creation_time_seconds: u64,
+    problem: Problem,
+    programming_language: String,
+    /// `None` if the submission is in the queue.
+    verdict: Option<String>,
+    passed_test_count: u64,
+    time_consumed_millis: u64,
+    memory_consumed_bytes: u64,
+}
+
+#[derive(Deserialize, Debug)]
+struct Problem {
+    index: String,
+    name: String,
+}
+
+impl Submission {
+    fn is_in_progress(&self) -> bool {
+        matches!(self.verdict.as_deref(), None | Some("TESTING"))
+    }
+
+    /// Returns the verdict as shown on the website, e.g. "Wrong answer on test 3".
+    fn verdict(&self) -> String {
+        let test = self.passed_test_count + 1;
+        match self.verdict.as_deref() {
+            None => "In queue".to_owned(),
+            Some("TESTING") => format!("Running on test {test}"),
+            Some("OK") => "Accepted".to_owned(),
+            Some(
+                verdict @ ("WRONG_ANSWER"
+                | "PRESENTATION_ERROR"
+                | "TIME_LIMIT_EXCEEDED"
+                | "MEMORY_LIMIT_EXCEEDED"
+                | "IDLENESS_LIMIT_EXCEEDED"
+                | "RUNTIME_ERROR"
+                | "SECURITY_VIOLATED"),
+            ) => format!("{} on test {}", capitalize(verdict), test),
+            Some(verdict) => capitalize(verdict),
+        }
+    }
+
+    fn style(&self) -> &'static str {
+        match self.verdict.as_deref() {
+            None | Some("TESTING") => ".bold",
+            Some("OK") => ".bold.green",
+            Some("TIME_LIMIT_EXCEEDED" | "MEMORY_LIMIT_EXCEEDED" | "IDLENESS_LIMIT_EXCEEDED") => {
+                ".bold.red"
+            }
+            Some(_) => ".bold.yellow",
+        }
+    }
+}
+
+/// `WRONG_ANSWER` → `Wrong answer`
+fn capitalize(verdict: &str) -> String {
+    let verdict = verdict.to_lowercase().replace('_', " ");
+    let mut chars = verdict.chars();
+    chars
+        .next()
+        .map(|c| c.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+struct Api<'a> {
+    client: reqwest::blocking::Client,
+    api_key: &'a str,
+    api_secret: &'a str,
+}
+
+impl<'a> Api<'a> {
+    fn new(api_key: &'a str, api_secret: &'a str) -> anyhow::Result<Self> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(super::TIMEOUT)
+            .build()?;
+        Ok(Self {
+            client,
+            api_key,
+            api_secret,
+        })
+    }
+
+    /// Calls `contest.status`. The submissions are in descending order of the IDs.
+    fn contest_status(
+        &self,
+        contest_id: u64,
+        handle: &str,
+        count: usize,
+    ) -> anyhow::Result<Vec<Submission>> {
+        self.call(
+            "contest.status",
+            &[
+                ("contestId", &contest_id.to_string()),
+                ("count", &count.to_string()),
+                ("from", "1"),
+                ("handle", handle),
+            ],
+        )
+    }
+
+    /// Calls `user.info`, which fails if the user does not exist.
+    fn user_info(&self, handle: &str) -> anyhow::Result<()> {
+        self.call::<IgnoredAny>("user.info", &[("handles", handle)])?;
+        Ok(())
+    }
+
+    /// Calls an authorized method. See <https://codeforces.com/apiHelp>.
+    fn call<T: for<'de> Deserialize<'de>>(
+        &self,
+        method: &str,
+        params: &[(&str, &str)],
+    ) -> anyhow::Result<T> {
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)?
+            .as_secs()
+            .to_string();
+
+        let mut params = params.to_vec();
+        params.extend([("apiKey", self.api_key), ("time", &time)]);
+        params.sort_unstable();
+
+        let mut url = format!("https://codeforces.com/api/{method}")
+            .parse::<Url>()
+            .unwrap();
+        url.query_pairs_mut().extend_pairs(&params);
+
+        let rand = 100_000 + SystemTime::now().duration_since(UNIX_EPOCH)?.subsec_nanos() % 900_000;
+        let api_sig = Sha512::digest(
+            format!(
+                "{}/{}?{}#{}",
+                rand,
+                method,
+                url.query().unwrap_or_default(),
+                self.api_secret,
+            )
+            .as_ref(),
+        )
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect::<String>();
+        url.query_pairs_mut()
+            .append_pair("apiSig", &format!("{rand}{api_sig}"));
+
+        let res = self
+            .client
+            .get(url)
+            .send()
+            .with_context(|| format!("could not call `{method}`"))?;
+        let res = serde_json::from_str::<Response<T>>(&res.text()?)
+            .with_context(|| format!("could not parse the response of `{method}`"))?;
+        return match res {
+            Response {
+                result: Some(result),
+                ..
+            } => Ok(result),
+            Response { comment, .. } => Err(anyhow!(
+                "`{}` failed: {}",
+                method,
+                comment.as_deref().unwrap_or("unknown error"),
+            )),
+        };
+
+        #[derive(Deserialize)]
+        struct Response<T> {
+            result: Option<T>,
+            comment: Option<String>,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::web::codeforces::{capitalize, is_valid_handle, parse_submission_url};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_submission_urls() -> anyhow::Result<()> {
+        assert_eq!(
+            (1234, 56_789_012),
+            parse_submission_url(
+                &"https://codeforces.com/contest/1234/submission/56789012".parse()?
+            )?,
+        );
+        assert!(parse_submission_url(&"https://codeforces.com/contest/1234/my".parse()?).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn capitalize_verdicts() {
+        assert_eq!("Wrong answer", capitalize("WRONG_ANSWER"));
+        assert_eq!("Compilation error", capitalize("COMPILATION_ERROR"));
+    }
+
+    #[test]
+    fn valid_handles() {
+        assert!(is_valid_handle("tourist"));
+        assert!(is_valid_handle("a.b-c_1"));
+        assert!(!is_valid_handle(""));
+        assert!(!is_valid_handle("ab"));
+        assert!(!is_valid_handle("tourist "));
+        assert!(!is_valid_handle(&"a".repeat(25)));
+    }
+}
@@ -99,6 +99,38 @@ pub(crate) fn codeforces_api_key_and_secret(shell: &mut Shell) -> anyhow::Result
     }
 }
 
+/// Returns the Codeforces handle, asking for it if it is not saved.
+///
+/// An entered handle is saved only if `check` accepts it. Otherwise it is asked again, up to 3
+/// times.
+pub(crate) fn codeforces_handle(
+    shell: &mut Shell,
+    mut check: impl FnMut(&str) -> anyhow::Result<()>,
+) -> anyhow::Result<String> {
+    if let Some(value) = env_var("CODEFORCES_HANDLE")? {
+        return Ok(value);
+    }
+
+    let path = token_path("codeforces-handle.json")?;
+    if path.exists() {
+        return crate::fs::read_json(path);
+    }
+
+    let mut remaining = 3;
+    let handle = loop {
+        let handle = shell.read_reply("Codeforces handle: ")?;
+        remaining -= 1;
+        match check(&handle) {
+            Ok(()) => break handle,
+            Err(err) if remaining > 0 => shell.warn(format!("{err:#}"))?,
+            Err(err) => return Err(err),
+        }
+    };
+    crate::fs::create_dir_all(path.parent().unwrap())?;
+    crate::fs::write_json(path, &handle)?;
+    Ok(handle)
+}
+
 fn env_var(name: &str) -> anyhow::Result<Option<String>> {
     env::var_os(name)
         .map(|v| {
@@ -1,3 +1,4 @@
+pub(crate) mod codeforces;
 pub(crate) mod credentials;
 pub(crate) mod retrieve_testcases;
 pub(crate) mod url;
@@ -9,7 +9,9 @@ use camino::{Utf8Path, Utf8PathBuf};
 use cargo_metadata as cm;
 use indexmap::{indexmap, IndexMap};
 use maplit::{btreemap, btreeset};
+use once_cell::sync::Lazy;
 use percent_encoding::PercentDecode;
+use regex::Regex;
 use snowchains_core::{
     testsuite::{Additional, BatchTestSuite, Match, PartialBatchTestCase, TestSuite},
     web::{
@@ -122,7 +124,7 @@ pub(crate) fn dl_for_existing_package(
     let mut bin_name_aliases = bin_name_aliases.cloned();
     let mut example_name_aliases = example_name_aliases.cloned();
 
-    for (name, PackageMetadataCargoCompeteBinExample { alias, problem }) in itertools::chain(
+    for (name, PackageMetadataCargoCompeteBinExample { alias, problem, .. }) in itertools::chain(
         package_metadata_bin.iter().filter(
             |&(name, PackageMetadataCargoCompeteBinExample { alias, .. })| {
                 bin_name_aliases
@@ -240,7 +242,7 @@ pub(crate) fn dl_from_atcoder(
 
     let cookie_storage = CookieStorage::with_jsonl(cookies_path)?;
 
-    Atcoder::exec(RetrieveTestCases {
+    let problems = Atcoder::exec(RetrieveTestCases {
         targets,
         credentials,
         full,
@@ -248,7 +250,12 @@ pub(crate) fn dl_from_atcoder(
         timeout: crate::web::TIMEOUT,
         shell: &shell,
     })
-    .map(|RetrieveTestCasesOutcome { problems, .. }| problems.into_iter().map(Into::into).collect())
+    .map(|RetrieveTestCasesOutcome { problems, .. }| {
+        problems.into_iter().map(Into::into).collect()
+    })?;
+
+    let problems = with_memory_limits(problems, &mut shell.borrow_mut());
+    problems
 }
 
 pub(crate) fn dl_from_codeforces(
@@ -268,7 +275,7 @@ pub(crate) fn dl_from_codeforces(
 
     let cookie_storage = CookieStorage::with_jsonl(cookies_path)?;
 
-    Codeforces::exec(RetrieveTestCases {
+    let problems = Codeforces::exec(RetrieveTestCases {
         targets,
         credentials,
         full: None,
@@ -276,7 +283,12 @@ pub(crate) fn dl_from_codeforces(
         timeout: crate::web::TIMEOUT,
         shell: &shell,
     })
-    .map(|RetrieveTestCasesOutcome { problems, .. }| problems.into_iter().map(Into::into).collect())
+    .map(|RetrieveTestCasesOutcome { problems, .. }| {
+        problems.into_iter().map(Into::into).collect()
+    })?;
+
+    let problems = with_memory_limits(problems, &mut shell.borrow_mut());
+    problems
 }
 
 pub(crate) fn dl_from_yukicoder(
@@ -296,7 +308,7 @@ pub(crate) fn dl_from_yukicoder(
 
     let shell = RefCell::new(shell.borrow_mut());
 
-    Yukicoder::exec(RetrieveTestCases {
+    let problems = Yukicoder::exec(RetrieveTestCases {
         targets,
         credentials: (),
         full,
@@ -304,7 +316,55 @@ pub(crate) fn dl_from_yukicoder(
         timeout: crate::web::TIMEOUT,
         shell: &shell,
     })
-    .map(|RetrieveTestCasesOutcome { problems, .. }| problems.into_iter().map(Into::into).collect())
+    .map(|RetrieveTestCasesOutcome { problems, .. }| {
+        problems.into_iter().map(Into::into).collect()
+    })?;
+
+    let problems = with_memory_limits(problems, &mut shell.borrow_mut());
+    problems
+}
+
+/// Fills `memory_limit` from the problem pages, since `snowchains_core` does not retrieve it.
+fn with_memory_limits(
+    mut problems: Vec<Problem<String>>,
+    shell: &mut Shell,
+) -> anyhow::Result<Vec<Problem<String>>> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(crate::web::TIMEOUT)
+        .build()?;
+
+    for problem in &mut problems {
+        let html = client
+            .get(problem.url.clone())
+            .send()
+            .and_then(reqwest::blocking::Response::error_for_status)
+            .and_then(reqwest::blocking::Response::text);
+        match html {
+            Ok(html) => problem.memory_limit = parse_memory_limit(&html),
+            Err(err) => shell.warn(format!(
+                "could not retrieve the memory limit of {}: {}",
+                problem.url, err,
+            ))?,
+        }
+    }
+    Ok(problems)
+}
+
+/// Finds the memory limit in MiB in a problem page of AtCoder, Codeforces, or yukicoder.
+fn parse_memory_limit(html: &str) -> Option<u64> {
+    static MEMORY_LIMIT: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(
+            r"(?:Memory Limit|memory limit per test</div>|メモリ制限)\s*:?\s*([0-9]+)\s*(MiB|MB|megabytes|KiB|KB|kilobytes)",
+        )
+        .unwrap()
+    });
+
+    let caps = MEMORY_LIMIT.captures(html)?;
+    let value = caps[1].parse::<u64>().ok()?;
+    match &caps[2] {
+        "KiB" | "KB" | "kilobytes" => Some(value / 1024),
+        _ => Some(value),
+    }
 }
 
 pub(crate) fn system_test_cases_dir(problem_url: &Url) -> anyhow::Result<PathBuf> {
@@ -338,6 +398,7 @@ pub(crate) fn save_test_cases<I>(
         index,
         url,
         mut test_suite,
+        memory_limit,
         text_files,
         ..
     } in problems
@@ -420,7 +481,10 @@ pub(crate) fn save_test_cases<I>(
             }
 
             crate::fs::create_dir_all(path.parent().unwrap())?;
-            crate::fs::write(&path, test_suite.to_yaml_pretty())?;
+            crate::fs::write(
+                &path,
+                with_memory_limit(test_suite.to_yaml_pretty(), memory_limit),
+            )?;
 
             shell.status(
                 "Saved",
@@ -456,11 +520,33 @@ pub(crate) fn save_test_cases<I>(
     Ok(acc)
 }
 
+/// Inserts `memorylimit`, which `snowchains_core` does not know, next to `timelimit`.
+fn with_memory_limit(yaml: String, memory_limit: Option<u64>) -> String {
+    let memory_limit = match memory_limit {
+        Some(memory_limit) => memory_limit,
+        None => return yaml,
+    };
+
+    let mut lines = yaml.lines().collect::<Vec<_>>();
+    let pos = lines
+        .iter()
+        .position(|l| l.starts_with("timelimit:"))
+        .or_else(|| lines.iter().position(|l| *l == "type: Batch"));
+    let memory_limit = format!("memorylimit: {memory_limit} MiB");
+    match pos {
+        Some(pos) => lines.insert(pos + 1, &memory_limit),
+        None => return yaml,
+    }
+    lines.join("\n") + "\n"
+}
+
 #[derive(Debug)]
 pub(crate) struct Problem<I> {
     pub(crate) index: I,
     pub(crate) url: Url,
     pub(crate) test_suite: TestSuite,
+    /// In MiB.
+    pub(crate) memory_limit: Option<u64>,
     pub(crate) text_files: IndexMap<String, (String, Option<String>)>,
     pub(crate) contest_url: Option<Url>,
 }
@@ -523,6 +609,7 @@ impl Problem<Option<String>> {
                 cases,
                 extend: vec![],
             }),
+            memory_limit: problem.memory_limit,
             text_files,
             contest_url: problem.context.contest.as_ref().and_then(|c| c.url.clone()),
         }
@@ -538,6 +625,7 @@ impl Problem<String> {
             index,
             url,
             test_suite,
+            memory_limit,
             text_files,
             contest_url,
         } = Problem::<Option<String>>::from_oj_api(problem, system);
@@ -549,6 +637,7 @@ impl Problem<String> {
             index,
             url,
             test_suite,
+            memory_limit,
             text_files,
             contest_url,
         })
@@ -561,6 +650,7 @@ impl From<Problem<String>> for Problem<Option<String>> {
             index: Some(problem.index),
             url: problem.url,
             test_suite: problem.test_suite,
+            memory_limit: problem.memory_limit,
             text_files: problem.text_files,
             contest_url: problem.contest_url,
         }
@@ -573,6 +663,7 @@ impl From<snowchains_core::web::RetrieveTestCasesOutcomeProblem> for Problem<Str
             index: problem.index,
             url: problem.url,
             test_suite: problem.test_suite,
+            memory_limit: None,
             text_files: problem
                 .text_files
                 .into_iter()
@@ -588,3 +679,69 @@ impl From<snowchains_core::web::RetrieveTestCasesOutcomeProblem> for Problem<Opt
         Problem::<String>::from(problem).into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::web::retrieve_testcases::{parse_memory_limit, with_memory_limit};
+    use pretty_assertions::assert_eq;
+    use snowchains_core::testsuite::{BatchTestSuite, Match, PartialBatchTestCase, TestSuite};
+    use std::time::Duration;
+
+    #[test]
+    fn parse_memory_limits() {
+        assert_eq!(
+            Some(1024),
+            parse_memory_limit("<p>Time Limit: 2 sec / Memory Limit: 1024 MiB</p>"),
+        );
+        assert_eq!(
+            Some(1024),
+            parse_memory_limit("<p>実行時間制限: 2 sec / メモリ制限: 1024 MiB</p>"),
+        );
+        assert_eq!(
+            Some(256),
+            parse_memory_limit(
+                r#"<div class="memory-limit"><div class="property-title">memory limit per test</div>256 megabytes</div>"#,
+            ),
+        );
+        assert_eq!(
+            Some(512),
+            parse_memory_limit("実行時間制限 : 1ケース 2.000秒 / メモリ制限 : 512 MB"),
+        );
+        assert_eq!(None, parse_memory_limit("<p>Time Limit: 2 sec</p>"));
+    }
+
+    #[test]
+    fn saved_yaml_contains_memorylimit() -> anyhow::Result<()> {
+        let test_suite = TestSuite::Batch(BatchTestSuite {
+            timelimit: Some(Duration::from_secs(2)),
+            r#match: Match::Lines,
+            cases: vec![PartialBatchTestCase {
+                name: Some("sample1".to_owned()),
+                r#in: "1 2\n".into(),
+                out: Some("3\n".into()),
+                timelimit: None,
+                r#match: None,
+            }],
+            extend: vec![],
+        });
+
+        let yaml = with_memory_limit(test_suite.to_yaml_pretty(), Some(1024));
+        let lines = yaml.lines().collect::<Vec<_>>();
+        let pos = lines
+            .iter()
+            .position(|l| l.starts_with("timelimit:"))
+            .unwrap();
+        assert_eq!("memorylimit: 1024 MiB", lines[pos + 1]);
+
+        let yaml = serde_yaml::from_str::<serde_yaml::Value>(&yaml)?;
+        assert_eq!(Some("1024 MiB"), yaml["memorylimit"].as_str());
+        assert_eq!(
+            serde_yaml::from_str::<TestSuite>(&test_suite.to_yaml_pretty())?,
+            serde_yaml::from_str::<TestSuite>(&with_memory_limit(
+                test_suite.to_yaml_pretty(),
+                Some(1024),
+            ))?,
+        );
+        Ok(())
+    }
+}
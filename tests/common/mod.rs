@@ -33,6 +33,20 @@ pub fn run(
     process_output: impl FnOnce(&Path, String) -> String,
     walk_override: impl FnOnce(&Path) -> Result<Override, ignore::Error>,
 ) -> anyhow::Result<(String, serde_json::Value)> {
+    let (result, output, tree) =
+        run_allowing_failure(before, input, args, process_output, walk_override)?;
+    result?;
+    Ok((output, tree))
+}
+
+/// Same as [`run`], but returns the output even if the command fails.
+pub fn run_allowing_failure(
+    before: impl FnOnce(&Path) -> anyhow::Result<()>,
+    input: impl BufRead + 'static,
+    args: &[&str],
+    process_output: impl FnOnce(&Path, String) -> String,
+    walk_override: impl FnOnce(&Path) -> Result<Override, ignore::Error>,
+) -> anyhow::Result<(anyhow::Result<()>, String, serde_json::Value)> {
     let workspace = tempfile::Builder::new()
         .prefix("cargo-compete-test-workspace")
         .tempdir()?;
@@ -52,14 +66,15 @@ pub fn run(
 
     let Opt::Compete(opt) = Opt::from_iter_safe(args)?;
 
-    cargo_compete::run(
+    let result = cargo_compete::run(
         opt,
         cargo_compete::Context {
             cwd: workspace.path().to_owned(),
             cookies_path: Path::new(&cookies_jsonl).to_owned(),
             shell: &mut Shell::from_read_write(Box::new(input), Box::new(output_file)),
+            cargo_compete_exe: Some(env!("CARGO_BIN_EXE_cargo-compete").into()),
         },
-    )?;
+    );
 
     let output_content = process_output(workspace.path(), std::fs::read_to_string(&output)?);
     let tree = tree(workspace.as_ref(), walk_override(workspace.path())?)?;
@@ -67,7 +82,7 @@ pub fn run(
     workspace.close()?;
     output.close()?;
 
-    Ok((output_content, tree))
+    Ok((result, output_content, tree))
 }
 
 fn tree(path: &Path, walk_override: Override) -> anyhow::Result<serde_json::Value> {
@@ -0,0 +1,151 @@
+pub mod common;
+
+use ignore::overrides::OverrideBuilder;
+use insta::assert_snapshot;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::io;
+
+#[test]
+fn atcoder_tokens() -> anyhow::Result<()> {
+    let (output, tree) = run("long.txt", "3\n1 7  2\r\n5 7 9\n")?;
+
+    assert_snapshot!("atcoder_tokens_output", output);
+    assert_eq!(
+        "7\n",
+        tree["practice"]["testcases"]["a"]["in"]["long-min.txt"]
+    );
+    Ok(())
+}
+
+#[test]
+fn atcoder_already_minimal() -> anyhow::Result<()> {
+    let (output, tree) = run("short.txt", "7")?;
+
+    assert_snapshot!("atcoder_already_minimal_output", output);
+    assert_eq!(
+        "7\n",
+        tree["practice"]["testcases"]["a"]["in"]["short-min.txt"]
+    );
+    Ok(())
+}
+
+fn run(file_name: &str, input: &str) -> anyhow::Result<(String, serde_json::Value)> {
+    common::run(
+        |cwd| -> _ {
+            std::fs::write(
+                cwd.join("compete.toml"),
+                r#"test-suite = "{{ manifest_dir }}/testcases/{{ bin_alias | kebabcase }}.yml"
+
+[template]
+src = '''
+fn main() {
+    todo!();
+}
+'''
+
+[new]
+platform = "atcoder"
+path = "./{{ package_name }}"
+"#,
+            )?;
+
+            std::fs::create_dir(cwd.join(".cargo"))?;
+
+            std::fs::write(
+                cwd.join(".cargo").join("config.toml"),
+                r#"[build]
+target-dir = "target"
+"#,
+            )?;
+
+            std::fs::create_dir_all(cwd.join("practice").join("src").join("bin"))?;
+            std::fs::create_dir_all(cwd.join("practice").join("testcases"))?;
+
+            std::fs::write(
+                cwd.join("practice").join("Cargo.toml"),
+                r#"[package]
+name = "practice"
+version = "0.1.0"
+edition = "2018"
+
+[package.metadata.cargo-compete.bin]
+practice-a = { alias = "a", problem = "https://atcoder.jp/contests/practice/tasks/practice_1" }
+
+[[bin]]
+name = "practice-a"
+path = "src/bin/a.rs"
+
+[dependencies]
+"#,
+            )?;
+
+            std::fs::write(
+                cwd.join("practice").join("testcases").join("a.yml"),
+                r#"---
+type: Batch
+timelimit: 2s
+match: Lines
+
+cases: []
+
+extend:
+  - type: Text
+    path: "./a"
+    in: /in/*.txt
+    out: /out/*.txt
+"#,
+            )?;
+
+            std::fs::write(
+                cwd.join("practice").join("src").join("bin").join("a.rs"),
+                r#"use std::io::{self, Read as _};
+
+fn main() {
+    let mut input = "".to_owned();
+    io::stdin().read_to_string(&mut input).unwrap();
+    if input.split_whitespace().any(|s| s == "7") {
+        std::process::exit(1);
+    }
+    println!("0");
+}
+"#,
+            )?;
+
+            std::fs::write(cwd.join(file_name), input)?;
+            Ok(())
+        },
+        io::empty(),
+        &[
+            "",
+            "compete",
+            "minimize",
+            "--input",
+            &format!("./{}", file_name),
+            "--granularity",
+            "tokens",
+            "--manifest-path",
+            "./practice/Cargo.toml",
+            "a",
+        ],
+        |workspace_root, output| {
+            macro_rules! lazy_regex(($regex:literal) => (Lazy::new(|| Regex::new($regex).unwrap())));
+
+            static RUNNING: Lazy<Regex> = lazy_regex!("(?m)^     Running `[^`]+`");
+            static RUNTIME_ERROR: Lazy<Regex> =
+                lazy_regex!(r"Runtime Error \([0-9]+ ms(, [0-9.]+ [KM]iB)?,");
+            static FINISHED: Lazy<Regex> = lazy_regex!(r"in [0-9]+ runs?");
+
+            let output = output.replace(workspace_root.to_str().unwrap(), "{{ cwd }}");
+            let output = RUNNING.replace_all(&output, "     Running {{ command }}");
+            let output = RUNTIME_ERROR.replace_all(&output, "Runtime Error ({{ usage }},");
+            let output = FINISHED.replace_all(&output, "in {{ runs }}");
+            output.into_owned()
+        },
+        |workspace_root| {
+            OverrideBuilder::new(workspace_root)
+                .add("!/target/")?
+                .build()
+        },
+    )
+}
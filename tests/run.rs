@@ -0,0 +1,141 @@
+pub mod common;
+
+use ignore::overrides::OverrideBuilder;
+use insta::assert_snapshot;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::io;
+
+#[test]
+fn atcoder_input_text() -> anyhow::Result<()> {
+    let (result, output, tree) = run(
+        &["1 2"],
+        r#"use std::io::{self, Read as _};
+
+fn main() {
+    let mut input = "".to_owned();
+    io::stdin().read_to_string(&mut input).unwrap();
+    let sum = input
+        .split_whitespace()
+        .map(|s| s.parse::<u32>().unwrap())
+        .sum::<u32>();
+    std::fs::write("answer.txt", format!("{}\n", sum)).unwrap();
+}
+"#,
+    )?;
+
+    result?;
+    assert_snapshot!("atcoder_input_text_output", output);
+    assert_eq!("3\n", tree["practice"]["answer.txt"]);
+    Ok(())
+}
+
+#[test]
+fn atcoder_exit_failure() -> anyhow::Result<()> {
+    let (result, output, _) = run(
+        &[""],
+        r#"fn main() {
+    std::process::exit(1);
+}
+"#,
+    )?;
+
+    assert_eq!(
+        "`practice-a` exited unsuccessfully",
+        result.unwrap_err().to_string(),
+    );
+    assert_snapshot!("atcoder_exit_failure_output", output);
+    Ok(())
+}
+
+fn run(
+    input_text: &[&str],
+    code: &str,
+) -> anyhow::Result<(anyhow::Result<()>, String, serde_json::Value)> {
+    let args = [
+        "",
+        "compete",
+        "run",
+        "a",
+        "--manifest-path",
+        "./practice/Cargo.toml",
+    ]
+    .iter()
+    .copied()
+    .chain(input_text.iter().flat_map(|&s| ["--input-text", s]))
+    .collect::<Vec<_>>();
+
+    common::run_allowing_failure(
+        |cwd| -> _ {
+            std::fs::write(
+                cwd.join("compete.toml"),
+                r#"test-suite = "{{ manifest_dir }}/testcases/{{ bin_alias | kebabcase }}.yml"
+
+[template]
+src = '''
+fn main() {
+    todo!();
+}
+'''
+
+[new]
+platform = "atcoder"
+path = "./{{ package_name }}"
+"#,
+            )?;
+
+            std::fs::create_dir(cwd.join(".cargo"))?;
+
+            std::fs::write(
+                cwd.join(".cargo").join("config.toml"),
+                r#"[build]
+target-dir = "target"
+"#,
+            )?;
+
+            std::fs::create_dir_all(cwd.join("practice").join("src").join("bin"))?;
+
+            std::fs::write(
+                cwd.join("practice").join("Cargo.toml"),
+                r#"[package]
+name = "practice"
+version = "0.1.0"
+edition = "2018"
+
+[package.metadata.cargo-compete.bin]
+practice-a = { alias = "a", problem = "https://atcoder.jp/contests/practice/tasks/practice_1" }
+
+[[bin]]
+name = "practice-a"
+path = "src/bin/a.rs"
+
+[dependencies]
+"#,
+            )?;
+
+            std::fs::write(
+                cwd.join("practice").join("src").join("bin").join("a.rs"),
+                code,
+            )?;
+            Ok(())
+        },
+        io::empty(),
+        &args,
+        |_, output| {
+            macro_rules! lazy_regex(($regex:literal) => (Lazy::new(|| Regex::new($regex).unwrap())));
+
+            static RUNNING: Lazy<Regex> = lazy_regex!("(?m)^     Running `[^`]+`");
+            static FINISHED: Lazy<Regex> =
+                lazy_regex!(r"(exit (status|code): [0-9]+) \([0-9]+ ms(, [0-9.]+ [KM]iB)?\)");
+
+            let output = RUNNING.replace_all(&output, "     Running {{ command }}");
+            let output = FINISHED.replace_all(&output, "$1 ({{ elapsed }})");
+            output.into_owned()
+        },
+        |workspace_root| {
+            OverrideBuilder::new(workspace_root)
+                .add("!/target/")?
+                .build()
+        },
+    )
+}
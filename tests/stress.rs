@@ -0,0 +1,177 @@
+pub mod common;
+
+use ignore::overrides::OverrideBuilder;
+use insta::assert_snapshot;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::io;
+
+#[test]
+fn atcoder_found() -> anyhow::Result<()> {
+    let (result, output, tree) = run(&["--seed", "5"])?;
+
+    assert_eq!(
+        "found a failing input after 4 iterations (seed = 8)",
+        result.unwrap_err().to_string(),
+    );
+    assert_snapshot!("atcoder_found_output", output);
+    assert_eq!(
+        "8\n",
+        tree["practice"]["testcases"]["a"]["in"]["stress-8.txt"]
+    );
+    assert_eq!(
+        "8\n",
+        tree["practice"]["testcases"]["a"]["out"]["stress-8.txt"]
+    );
+    Ok(())
+}
+
+#[test]
+fn atcoder_not_found() -> anyhow::Result<()> {
+    let (result, output, tree) = run(&["--seed", "0", "-n", "3"])?;
+
+    result?;
+    assert_snapshot!("atcoder_not_found_output", output);
+    assert!(tree["practice"]["testcases"].get("a").is_none());
+    Ok(())
+}
+
+fn run(opts: &[&str]) -> anyhow::Result<(anyhow::Result<()>, String, serde_json::Value)> {
+    let args = [
+        "",
+        "compete",
+        "stress",
+        "--generator",
+        "gen",
+        "--reference",
+        "brute",
+    ]
+    .iter()
+    .chain(opts)
+    .chain(&["--manifest-path", "./practice/Cargo.toml", "a"])
+    .copied()
+    .collect::<Vec<_>>();
+
+    common::run_allowing_failure(
+        |cwd| -> _ {
+            std::fs::write(
+                cwd.join("compete.toml"),
+                r#"test-suite = "{{ manifest_dir }}/testcases/{{ bin_alias | kebabcase }}.yml"
+
+[template]
+src = '''
+fn main() {
+    todo!();
+}
+'''
+
+[new]
+platform = "atcoder"
+path = "./{{ package_name }}"
+"#,
+            )?;
+
+            std::fs::create_dir(cwd.join(".cargo"))?;
+
+            std::fs::write(
+                cwd.join(".cargo").join("config.toml"),
+                r#"[build]
+target-dir = "target"
+"#,
+            )?;
+
+            std::fs::create_dir_all(cwd.join("practice").join("src").join("bin"))?;
+            std::fs::create_dir_all(cwd.join("practice").join("examples"))?;
+            std::fs::create_dir_all(cwd.join("practice").join("testcases"))?;
+
+            std::fs::write(
+                cwd.join("practice").join("Cargo.toml"),
+                r#"[package]
+name = "practice"
+version = "0.1.0"
+edition = "2018"
+
+[package.metadata.cargo-compete.bin]
+practice-a = { alias = "a", problem = "https://atcoder.jp/contests/practice/tasks/practice_1" }
+
+[[bin]]
+name = "practice-a"
+path = "src/bin/a.rs"
+
+[dependencies]
+"#,
+            )?;
+
+            // Does not load `./a`.
+            std::fs::write(
+                cwd.join("practice").join("testcases").join("a.yml"),
+                r#"---
+type: Batch
+timelimit: 2s
+match: Lines
+
+cases: []
+
+extend: []
+"#,
+            )?;
+
+            std::fs::write(
+                cwd.join("practice").join("examples").join("gen.rs"),
+                r#"fn main() {
+    let seed = std::env::args().nth(1).unwrap();
+    println!("{}", seed);
+}
+"#,
+            )?;
+
+            std::fs::write(
+                cwd.join("practice").join("examples").join("brute.rs"),
+                r#"use std::io::{self, Read as _};
+
+fn main() {
+    let mut input = "".to_owned();
+    io::stdin().read_to_string(&mut input).unwrap();
+    let n = input.trim().parse::<u64>().unwrap();
+    println!("{}", n);
+}
+"#,
+            )?;
+
+            std::fs::write(
+                cwd.join("practice").join("src").join("bin").join("a.rs"),
+                r#"use std::io::{self, Read as _};
+
+fn main() {
+    let mut input = "".to_owned();
+    io::stdin().read_to_string(&mut input).unwrap();
+    let n = input.trim().parse::<u64>().unwrap();
+    println!("{}", if n < 8 { n } else { n + 1 });
+}
+"#,
+            )?;
+            Ok(())
+        },
+        io::empty(),
+        &args,
+        |workspace_root, output| {
+            macro_rules! lazy_regex(($regex:literal) => (Lazy::new(|| Regex::new($regex).unwrap())));
+
+            static RUNNING: Lazy<Regex> = lazy_regex!("(?m)^     Running `[^`]+`");
+            static WRONG_ANSWER: Lazy<Regex> =
+                lazy_regex!(r"Wrong Answer \([0-9]+ ms(, [0-9.]+ [KM]iB)?\)");
+            static FINISHED: Lazy<Regex> = lazy_regex!(r"in [0-9]+\.[0-9]s\.");
+
+            let output = output.replace(workspace_root.to_str().unwrap(), "{{ cwd }}");
+            let output = RUNNING.replace_all(&output, "     Running {{ command }}");
+            let output = WRONG_ANSWER.replace_all(&output, "Wrong Answer ({{ usage }})");
+            let output = FINISHED.replace_all(&output, "in {{ elapsed }}.");
+            output.into_owned()
+        },
+        |workspace_root| {
+            OverrideBuilder::new(workspace_root)
+                .add("!/target/")?
+                .build()
+        },
+    )
+}
@@ -55,6 +55,186 @@ fn main() {
     Ok(())
 }
 
+#[test]
+fn atcoder_deep_recursion() -> anyhow::Result<()> {
+    // Runs with `exec-with-stack-size`, since the stack size on AtCoder is 1 GiB by default.
+    let (output, _) = run(
+        "practice",
+        "a",
+        "https://atcoder.jp/contests/practice/tasks/practice_1",
+        DEEP_RECURSION_TEST_SUITE,
+        DEEP_RECURSION_CODE,
+    )?;
+
+    assert_snapshot!("atcoder_deep_recursion_output", output);
+    Ok(())
+}
+
+#[test]
+fn yukicoder_stack_overflow() -> anyhow::Result<()> {
+    // Runs the artifact directly, since yukicoder has no default stack size.
+    let (result, output, _) = run_allowing_failure(
+        "practice",
+        "a",
+        "https://yukicoder.me/problems/no/1",
+        DEEP_RECURSION_TEST_SUITE,
+        None,
+        DEEP_RECURSION_CODE,
+    )?;
+
+    assert_eq!("1/1 test failed", result.unwrap_err().to_string());
+    assert!(output.contains("Runtime Error") && output.contains("stack overflow)"));
+    Ok(())
+}
+
+#[test]
+fn atcoder_memory_limit_exceeded() -> anyhow::Result<()> {
+    let (result, output, _) = run_allowing_failure(
+        "practice",
+        "a",
+        "https://atcoder.jp/contests/practice/tasks/practice_1",
+        r#"---
+type: Batch
+timelimit: 10s
+match: Lines
+memorylimit: 64 MiB
+
+cases:
+  - name: small
+    in: |
+      0
+    out: |
+      0
+  - name: large
+    in: |
+      256
+    out: |
+      1
+
+extend: []
+"#,
+        None,
+        r#"use proconio::input;
+use std::hint::black_box;
+
+fn main() {
+    input! {
+        mib: usize,
+    }
+
+    let v = black_box(vec![1u8; mib << 20]);
+    println!("{}", v.last().copied().unwrap_or(0));
+}
+"#,
+    )?;
+
+    assert_eq!("1/2 test failed", result.unwrap_err().to_string());
+    assert_snapshot!("atcoder_memory_limit_exceeded_output", output);
+    Ok(())
+}
+
+#[test]
+fn atcoder_interactive() -> anyhow::Result<()> {
+    let (result, output, _) = run_allowing_failure(
+        "practice",
+        "b",
+        "https://atcoder.jp/contests/practice/tasks/practice_2",
+        r#"---
+type: Interactive
+timelimit: 1s
+interactor: [bash, interactor.sh]
+
+cases:
+  - name: sample1
+    in: |
+      50
+  - name: out-of-range
+    in: |
+      101
+  - name: no-response
+    in: |
+      sleep
+"#,
+        Some(
+            r#"#!/bin/bash
+n=$(cat "$1")
+[ "$n" = sleep ] && exec sleep 10
+for _ in $(seq 7); do
+    read -r guess || exit 1
+    if [ "$guess" -eq "$n" ]; then
+        echo Correct
+        exit 0
+    elif [ "$guess" -lt "$n" ]; then
+        echo Higher
+    else
+        echo Lower
+    fi
+done
+exit 1
+"#,
+        ),
+        r#"use std::io::{self, BufRead as _};
+
+fn main() {
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    let (mut lo, mut hi) = (1, 100);
+
+    loop {
+        let mid = (lo + hi) / 2;
+        println!("{}", mid);
+
+        match lines.next() {
+            Some(Ok(reply)) if reply == "Correct" => return,
+            Some(Ok(reply)) if reply == "Higher" => lo = mid + 1,
+            Some(Ok(reply)) if reply == "Lower" => hi = mid - 1,
+            _ => return,
+        }
+    }
+}
+"#,
+    )?;
+
+    assert_eq!("2/3 tests failed", result.unwrap_err().to_string());
+    assert_snapshot!("atcoder_interactive_output", output);
+    Ok(())
+}
+
+static DEEP_RECURSION_TEST_SUITE: &str = r#"---
+type: Batch
+timelimit: 10s
+match: Lines
+
+cases:
+  - name: sample1
+    in: |
+      1000000
+    out: |
+      1000000
+
+extend: []
+"#;
+
+static DEEP_RECURSION_CODE: &str = r#"use proconio::input;
+use std::hint::black_box;
+
+fn main() {
+    input! {
+        n: u32,
+    }
+
+    println!("{}", depth(n));
+}
+
+fn depth(n: u32) -> u32 {
+    if n == 0 {
+        0
+    } else {
+        black_box(depth(n - 1)) + 1
+    }
+}
+"#;
+
 fn run(
     contest: &str,
     problem: &str,
@@ -62,7 +242,21 @@ fn run(
     test_suite: &str,
     code: &str,
 ) -> anyhow::Result<(String, serde_json::Value)> {
-    common::run(
+    let (result, output, tree) =
+        run_allowing_failure(contest, problem, url, test_suite, None, code)?;
+    result?;
+    Ok((output, tree))
+}
+
+fn run_allowing_failure(
+    contest: &str,
+    problem: &str,
+    url: &str,
+    test_suite: &str,
+    interactor: Option<&str>,
+    code: &str,
+) -> anyhow::Result<(anyhow::Result<()>, String, serde_json::Value)> {
+    common::run_allowing_failure(
         |cwd| -> _ {
             std::fs::write(
                 cwd.join("compete.toml"),
@@ -136,6 +330,13 @@ proconio = "=0.3.6"
                     .with_extension("yml"),
                 test_suite,
             )?;
+
+            if let Some(interactor) = interactor {
+                std::fs::write(
+                    cwd.join(contest).join("testcases").join("interactor.sh"),
+                    interactor,
+                )?;
+            }
             Ok(())
         },
         io::empty(),
@@ -151,10 +352,13 @@ proconio = "=0.3.6"
             macro_rules! lazy_regex(($regex:literal) => (Lazy::new(|| Regex::new($regex).unwrap())));
 
             static RUNNING: Lazy<Regex> = lazy_regex!("^     Running `[^`]+`");
-            static ACCEPTED: Lazy<Regex> = lazy_regex!(r"Accepted \([0-9]+ ms\)");
+            static ACCEPTED: Lazy<Regex> =
+                lazy_regex!(r"Accepted \([0-9]+ ms(, [0-9.]+ [KM]iB)?\)");
+            static WRONG_ANSWER: Lazy<Regex> = lazy_regex!(r"Wrong Answer \([0-9]+ ms");
 
             let output = RUNNING.replace(&output, "     Running {{ command }}");
             let output = ACCEPTED.replace_all(&output, "Accepted ({{ elapsed }}) ms)");
+            let output = WRONG_ANSWER.replace_all(&output, "Wrong Answer ({{ elapsed }} ms");
             output.into_owned()
         },
         |workspace_root| {